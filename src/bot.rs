@@ -1,9 +1,11 @@
+mod account;
 mod admin;
 mod arg;
 mod command;
 mod contract;
 mod functions;
 mod missions;
+mod setting;
 
 use std::sync::LazyLock;
 
@@ -0,0 +1,507 @@
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use itertools::Itertools as _;
+use teloxide::{prelude::Requester as _, types::Message};
+
+use crate::database::types::{Account, AccountGrowth, ContractSeason, SpaceShip, UserSetting};
+use crate::egg::{
+    calc_earning_bonus, decode_backup, earning_bonus_to_soul_power, egg_name_to_str, grade_to_str,
+    parse_duration_type, parse_num_with_unit, parse_spaceship, proto, query_periodicals,
+    reward_type_to_str, soul_power_to_role,
+    types::{FuelTank, SpaceShipInfo},
+};
+use crate::functions::build_reqwest_client;
+use crate::types::{return_tf_emoji, timestamp_to_string};
+use crate::{DEFAULT_GROWTH_DAYS, MAX_GROWTH_DAYS, SEASON_CACHE_PERIOD};
+
+use super::functions::replace_all;
+use super::{BotType, arg::NecessaryArg};
+
+async fn query_backup(arg: &NecessaryArg, account: &Account) -> Option<proto::Backup> {
+    let cache = arg
+        .database()
+        .account_cache_query(account.ei().to_string())
+        .await
+        .flatten()?;
+    decode_backup(cache.cache())
+        .inspect_err(|e| log::warn!("Decode {} cache error: {e:?}", account.ei()))
+        .ok()
+}
+
+fn fuel_line(
+    account: &Account,
+    backup: &proto::Backup,
+    alert: Option<(
+        proto::mission_info::Spaceship,
+        proto::mission_info::DurationType,
+    )>,
+) -> String {
+    let Some(tank) = FuelTank::from_backup(backup) else {
+        return format!("*{}*: tank not found", replace_all(account.name()));
+    };
+    let fuels = tank
+        .fuels()
+        .iter()
+        .filter(|(_, amount, _)| *amount > 0.0)
+        .map(|(egg, amount, limit)| {
+            format!(
+                "{}: {} / {}",
+                replace_all(&egg_name_to_str(*egg)),
+                replace_all(&parse_num_with_unit(*amount)),
+                replace_all(&parse_num_with_unit(*limit))
+            )
+        })
+        .join("\n");
+    let ready = alert.map(|(ship, duration_type)| {
+        format!(
+            "\nEnough for {} {}: {}",
+            replace_all(SpaceShipInfo::ship_friendly_name(ship)),
+            SpaceShip::duration_type_to_str(duration_type as i64),
+            match FuelTank::requirement(backup, ship, duration_type) {
+                Some(requirement) => return_tf_emoji(tank.is_enough(&requirement)),
+                None => "unknown, no previous mission of this kind",
+            }
+        )
+    });
+    format!(
+        "*{}* tank capacity {}:\n{}{}",
+        replace_all(account.name()),
+        replace_all(&parse_num_with_unit(tank.capacity())),
+        if fuels.is_empty() {
+            "Tank is empty".to_string()
+        } else {
+            fuels
+        },
+        ready.unwrap_or_default()
+    )
+}
+
+pub(super) async fn handle_fuel_command(
+    bot: BotType,
+    arg: Arc<NecessaryArg>,
+    msg: Message,
+    args: String,
+) -> anyhow::Result<()> {
+    let args = args.trim();
+
+    if let Some(alert) = args.strip_prefix("alert") {
+        let alert = alert.trim();
+        let fuel_alert = if alert.eq("off") {
+            None
+        } else {
+            let Some((ship, duration_type)) =
+                alert.rsplit_once(' ').and_then(|(ship, duration)| {
+                    parse_spaceship(ship).zip(parse_duration_type(duration.trim()))
+                })
+            else {
+                bot.send_message(
+                    msg.chat.id,
+                    "Invalid format, usage: `/fuel alert henerprise epic`",
+                )
+                .await?;
+                return Ok(());
+            };
+            Some((ship, duration_type))
+        };
+
+        arg.database()
+            .user_fuel_alert_update(
+                msg.chat.id.0,
+                fuel_alert.map(|(ship, duration_type)| (ship as i32, duration_type as i32)),
+            )
+            .await;
+
+        let text = match fuel_alert {
+            Some((ship, duration_type)) => {
+                // Requirement is learned from mission history, warn accounts without one
+                let mut unknown = Vec::new();
+                for account in arg
+                    .database()
+                    .account_query(Some(msg.chat.id.0))
+                    .await
+                    .ok_or_else(|| anyhow!("Query user accounts error"))?
+                {
+                    let known = query_backup(&arg, &account).await.is_some_and(|backup| {
+                        FuelTank::requirement(&backup, ship, duration_type).is_some()
+                    });
+                    if !known {
+                        unknown.push(replace_all(account.name()).into_owned());
+                    }
+                }
+                format!(
+                    "Will notify when tank has enough fuel for {} {}{}",
+                    replace_all(SpaceShipInfo::ship_friendly_name(ship)),
+                    SpaceShip::duration_type_to_str(duration_type as i64),
+                    if unknown.is_empty() {
+                        String::new()
+                    } else {
+                        format!(
+                            "\nFuel requirement is unknown for {}, the alert will work after this mission has been launched once\\.",
+                            unknown.join(", ")
+                        )
+                    }
+                )
+            }
+            None => "Fuel alert disabled\\.".to_string(),
+        };
+        bot.send_message(msg.chat.id, text).await?;
+        return Ok(());
+    }
+
+    let accounts = arg
+        .database()
+        .account_query(Some(msg.chat.id.0))
+        .await
+        .ok_or_else(|| anyhow!("Query user accounts error"))?;
+
+    if accounts.is_empty() {
+        bot.send_message(msg.chat.id, "Nothing found").await?;
+        return Ok(());
+    }
+
+    let alert = arg
+        .database()
+        .user_setting_query(msg.chat.id.0)
+        .await
+        .unwrap_or_else(|| UserSetting::new(msg.chat.id.0))
+        .fuel_alert();
+
+    let mut lines = Vec::new();
+    for account in &accounts {
+        lines.push(match query_backup(&arg, account).await {
+            Some(backup) => fuel_line(account, &backup, alert),
+            None => format!(
+                "*{}*: no cached data, wait for next fetch",
+                replace_all(account.name())
+            ),
+        });
+    }
+
+    bot.send_message(msg.chat.id, lines.join("\n\n")).await?;
+
+    Ok(())
+}
+
+fn profile_line(account: &Account, backup: &proto::Backup, tz: chrono_tz::Tz) -> String {
+    let Some(game) = backup.game.as_ref() else {
+        return format!("*{}*: game data not found", replace_all(account.name()));
+    };
+    let stats = backup.stats.clone().unwrap_or_default();
+    let earning_bonus = calc_earning_bonus(game);
+    format!(
+        "*{}*:\n\
+        Soul eggs: {}\n\
+        Prophecy eggs: {}\n\
+        Earning bonus: {}% \\({}\\)\n\
+        Golden eggs: {}\n\
+        Prestiges: {}\n\
+        Piggy bank: {}{}\n\
+        Last backup: {}",
+        replace_all(account.name()),
+        replace_all(&parse_num_with_unit(game.soul_eggs_d())),
+        game.eggs_of_prophecy(),
+        replace_all(&parse_num_with_unit(earning_bonus)),
+        soul_power_to_role(earning_bonus_to_soul_power(earning_bonus)),
+        replace_all(&parse_num_with_unit(
+            game.golden_eggs_earned()
+                .saturating_sub(game.golden_eggs_spent()) as f64
+        )),
+        stats.num_prestiges(),
+        replace_all(&parse_num_with_unit(game.piggy_bank() as f64)),
+        if stats.piggy_full() {
+            " \\(full\\)"
+        } else {
+            ""
+        },
+        replace_all(&timestamp_to_string(backup.approx_time() as i64, tz))
+    )
+}
+
+pub(super) async fn handle_profile_command(
+    bot: BotType,
+    arg: Arc<NecessaryArg>,
+    msg: Message,
+    account: String,
+) -> anyhow::Result<()> {
+    let account = account.trim();
+
+    let accounts = arg
+        .database()
+        .account_query(Some(msg.chat.id.0))
+        .await
+        .ok_or_else(|| anyhow!("Query user accounts error"))?
+        .into_iter()
+        .filter(|x| account.is_empty() || x.ei().eq(account) || x.name().eq(account))
+        .collect_vec();
+
+    if accounts.is_empty() {
+        bot.send_message(msg.chat.id, "Nothing found").await?;
+        return Ok(());
+    }
+
+    let tz = arg.timezone(msg.chat.id).await;
+
+    let mut lines = Vec::new();
+    for account in &accounts {
+        lines.push(match query_backup(&arg, account).await {
+            Some(backup) => profile_line(account, &backup, tz),
+            None => format!(
+                "*{}*: no cached data, wait for next fetch",
+                replace_all(account.name())
+            ),
+        });
+    }
+
+    bot.send_message(msg.chat.id, lines.join("\n\n")).await?;
+
+    Ok(())
+}
+
+fn growth_line(account: &Account, growth: &[AccountGrowth]) -> String {
+    let (Some(first), Some(last)) = (growth.first(), growth.last()) else {
+        return format!("*{}*: no history yet", replace_all(account.name()));
+    };
+    let role = |growth: &AccountGrowth| {
+        soul_power_to_role(earning_bonus_to_soul_power(growth.earning_bonus()))
+    };
+    let days = growth
+        .iter()
+        .map(|g| {
+            format!(
+                "{}: SE {}, PE {}, EB {}%",
+                chrono::DateTime::from_timestamp(g.day() * 86400, 0)
+                    .map(|t| t.format("%m\\-%d").to_string())
+                    .unwrap_or_default(),
+                replace_all(&parse_num_with_unit(g.soul_eggs())),
+                g.prophecy_eggs(),
+                replace_all(&parse_num_with_unit(g.earning_bonus()))
+            )
+        })
+        .join("\n");
+    format!(
+        "*{}*:\n{days}\nChange: SE \\+{}, PE \\+{}, EB x{} \\({} \\-\\> {}\\)",
+        replace_all(account.name()),
+        replace_all(&parse_num_with_unit(
+            (last.soul_eggs() - first.soul_eggs()).max(0.0)
+        )),
+        last.prophecy_eggs() - first.prophecy_eggs(),
+        replace_all(&format!(
+            "{:.2}",
+            last.earning_bonus() / first.earning_bonus().max(f64::MIN_POSITIVE)
+        )),
+        role(first),
+        role(last)
+    )
+}
+
+pub(super) async fn handle_growth_command(
+    bot: BotType,
+    arg: Arc<NecessaryArg>,
+    msg: Message,
+    args: String,
+) -> anyhow::Result<()> {
+    let args = args.trim();
+    let (account, days) = match args.rsplit_once(' ').unwrap_or(("", args)) {
+        (account, days) if days.parse::<i64>().is_ok() => (account.trim(), days.parse()?),
+        _ => (args, DEFAULT_GROWTH_DAYS),
+    };
+
+    if !(1..=MAX_GROWTH_DAYS).contains(&days) {
+        bot.send_message(
+            msg.chat.id,
+            format!("Days should be between 1 and {MAX_GROWTH_DAYS}\\."),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let accounts = arg
+        .database()
+        .account_query(Some(msg.chat.id.0))
+        .await
+        .ok_or_else(|| anyhow!("Query user accounts error"))?
+        .into_iter()
+        .filter(|x| account.is_empty() || x.ei().eq(account) || x.name().eq(account))
+        .collect_vec();
+
+    if accounts.is_empty() {
+        bot.send_message(msg.chat.id, "Nothing found").await?;
+        return Ok(());
+    }
+
+    let since = msg.date.timestamp() / 86400 - days + 1;
+
+    let mut lines = Vec::new();
+    for account in &accounts {
+        let growth = arg
+            .database()
+            .account_growth_query(account.ei().to_string(), since)
+            .await
+            .unwrap_or_default();
+        lines.push(growth_line(account, &growth));
+    }
+
+    bot.send_message(msg.chat.id, lines.join("\n\n")).await?;
+
+    Ok(())
+}
+
+async fn fetch_season(arg: &NecessaryArg, ei: &str) -> Option<ContractSeason> {
+    let cached = arg.database().contract_season_query().await.flatten();
+    if cached.as_ref().is_some_and(|season| {
+        kstool::time::get_current_second() as i64 - season.timestamp() < SEASON_CACHE_PERIOD
+    }) {
+        return cached;
+    }
+
+    let periodicals = match query_periodicals(&build_reqwest_client(), ei).await {
+        Ok(periodicals) => periodicals,
+        Err(e) => {
+            log::error!("Query periodicals error: {e:?}");
+            return cached;
+        }
+    };
+    let Some(contracts) = periodicals.contracts else {
+        return cached;
+    };
+    let Some(info) = contracts.current_season.as_ref() else {
+        return cached;
+    };
+    let season = ContractSeason::new(
+        info,
+        contracts
+            .contracts
+            .iter()
+            .filter(|contract| contract.season_id() == info.id())
+            .map(|contract| contract.identifier().to_string())
+            .collect(),
+    );
+    arg.database().contract_season_insert(season.clone()).await;
+    Some(season)
+}
+
+fn season_text(season: &ContractSeason, backup: &proto::Backup, tz: chrono_tz::Tz) -> String {
+    let cpi = backup
+        .contracts
+        .as_ref()
+        .and_then(|contracts| contracts.last_cpi.clone())
+        .unwrap_or_default();
+    let season_cxp = cpi
+        .season_progress
+        .iter()
+        .find(|progress| progress.season_id() == season.id())
+        .map(|progress| progress.total_cxp())
+        .unwrap_or(cpi.season_cxp());
+
+    let goals = season
+        .info()
+        .and_then(|info| {
+            info.grade_goals
+                .into_iter()
+                .find(|goals| goals.grade() == cpi.grade())
+        })
+        .map(|goals| {
+            goals
+                .goals
+                .iter()
+                .map(|goal| {
+                    format!(
+                        "{} {}: {} {}",
+                        return_tf_emoji(season_cxp >= goal.cxp()),
+                        replace_all(&parse_num_with_unit(goal.cxp())),
+                        replace_all(&parse_num_with_unit(goal.reward_amount())),
+                        replace_all(&if goal.reward_sub_type().is_empty() {
+                            reward_type_to_str(goal.reward_type())
+                        } else {
+                            goal.reward_sub_type().to_string()
+                        })
+                    )
+                })
+                .join("\n")
+        })
+        .unwrap_or_else(|| "Goals not found".into());
+
+    let joined = backup
+        .contracts
+        .iter()
+        .flat_map(|contracts| contracts.contracts.iter().chain(contracts.archive.iter()))
+        .filter_map(|local_contract| local_contract.contract.as_ref())
+        .map(|contract| contract.identifier())
+        .collect::<std::collections::HashSet<_>>();
+    let remaining = season
+        .contracts()
+        .into_iter()
+        .filter(|id| !joined.contains(id))
+        .collect_vec();
+
+    format!(
+        "*{}* \\(since {}\\)\nGrade: {}, season CS: {}\n{goals}\nRemaining season contracts: {}{}",
+        replace_all(season.name()),
+        replace_all(&timestamp_to_string(season.start_time() as i64, tz)),
+        grade_to_str(cpi.grade()),
+        replace_all(&parse_num_with_unit(season_cxp)),
+        remaining.len(),
+        if remaining.is_empty() {
+            String::new()
+        } else {
+            format!(
+                "\n{}",
+                remaining
+                    .iter()
+                    .map(|id| format!("`{}`", replace_all(id)))
+                    .join("\n")
+            )
+        }
+    )
+}
+
+pub(super) async fn handle_season_command(
+    bot: BotType,
+    arg: Arc<NecessaryArg>,
+    msg: Message,
+    ei: String,
+) -> anyhow::Result<()> {
+    let ei = ei.trim();
+
+    let Some(account) = arg
+        .database()
+        .account_query(if arg.check_admin(msg.chat.id) {
+            None
+        } else {
+            Some(msg.chat.id.0)
+        })
+        .await
+        .ok_or_else(|| anyhow!("Query user accounts error"))?
+        .into_iter()
+        .find(|x| x.ei().eq(ei))
+    else {
+        bot.send_message(msg.chat.id, "Account not found").await?;
+        return Ok(());
+    };
+
+    let Some(season) = fetch_season(&arg, account.ei()).await else {
+        bot.send_message(msg.chat.id, "Season information not available")
+            .await?;
+        return Ok(());
+    };
+
+    let Some(backup) = query_backup(&arg, &account).await else {
+        bot.send_message(msg.chat.id, "No cached data, wait for next fetch")
+            .await?;
+        return Ok(());
+    };
+
+    let tz = arg.timezone(msg.chat.id).await;
+
+    bot.send_message(msg.chat.id, season_text(&season, &backup, tz))
+        .await?;
+
+    Ok(())
+}
+
+pub(super) mod prelude {
+    pub(in crate::bot) use super::{
+        handle_fuel_command, handle_growth_command, handle_profile_command, handle_season_command,
+    };
+}
@@ -11,7 +11,7 @@ use anyhow::anyhow;
 use crate::{
     egg::{decode_coop_status, ei_request, query_coop_status},
     functions::build_reqwest_client,
-    types::timestamp_fmt,
+    types::{DEFAULT_TIMEZONE, timestamp_fmt},
 };
 
 use super::{
@@ -282,7 +282,8 @@ async fn handle_admin_sub_command(
                             ei.unwrap_or("None"),
                             timestamp_fmt(
                                 kstool::time::get_current_second() as i64,
-                                "%Y%m%d-%H%M%S"
+                                "%Y%m%d-%H%M%S",
+                                DEFAULT_TIMEZONE
                             )
                         )),
                     )
@@ -309,7 +310,8 @@ async fn handle_admin_sub_command(
                             "{ei}-{}.txt",
                             timestamp_fmt(
                                 kstool::time::get_current_second() as i64,
-                                "%Y%m%d-%H%M%S"
+                                "%Y%m%d-%H%M%S",
+                                DEFAULT_TIMEZONE
                             )
                         )),
                     )
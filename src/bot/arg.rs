@@ -1,8 +1,10 @@
+use chrono_tz::Tz;
 use teloxide::types::ChatId;
 
 use crate::{
     database::DatabaseHelper,
     egg::monitor::{ContractSubscriberHelper, MonitorHelper},
+    types::DEFAULT_TIMEZONE,
 };
 
 #[derive(Clone, Debug)]
@@ -54,4 +56,12 @@ impl NecessaryArg {
     pub(super) fn subscriber(&self) -> &ContractSubscriberHelper {
         &self.subscriber
     }
+
+    pub async fn timezone(&self, id: ChatId) -> Tz {
+        self.database
+            .user_setting_query(id.0)
+            .await
+            .map(|setting| setting.timezone())
+            .unwrap_or(DEFAULT_TIMEZONE)
+    }
 }
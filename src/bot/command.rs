@@ -24,8 +24,10 @@ use crate::{
     types::{BASE64, timestamp_to_string},
 };
 
+use super::account::prelude::*;
 use super::contract::{CONTRACT_WEBSITE_RE, COOP_ID_RE, ContractCommand, ROOM_RE, prelude::*};
 use super::missions::prelude::*;
+use super::setting::prelude::*;
 
 #[derive(BotCommands, Clone)]
 #[command(rename_rule = "snake_case")]
@@ -39,6 +41,15 @@ enum Command {
     Admin { line: String },
     Start { args: String },
     EpicExport { cmd: String },
+    Timezone { tz: String },
+    Reminder { minutes: String },
+    Quiet { window: String },
+    Idle { minutes: String },
+    Fuel { args: String },
+    Profile { account: String },
+    Growth { args: String },
+    Alert { args: String },
+    Season { ei: String },
     Help,
     Ping,
 }
@@ -122,6 +133,33 @@ pub async fn bot_run(
                             Command::EpicExport { cmd } => {
                                 handle_epic_export_command(bot, arg, msg, cmd).await
                             }
+                            Command::Timezone { tz } => {
+                                handle_timezone_command(bot, arg, msg, tz).await
+                            }
+                            Command::Reminder { minutes } => {
+                                handle_reminder_command(bot, arg, msg, minutes).await
+                            }
+                            Command::Quiet { window } => {
+                                handle_quiet_command(bot, arg, msg, window).await
+                            }
+                            Command::Idle { minutes } => {
+                                handle_idle_command(bot, arg, msg, minutes).await
+                            }
+                            Command::Fuel { args } => {
+                                handle_fuel_command(bot, arg, msg, args).await
+                            }
+                            Command::Profile { account } => {
+                                handle_profile_command(bot, arg, msg, account).await
+                            }
+                            Command::Growth { args } => {
+                                handle_growth_command(bot, arg, msg, args).await
+                            }
+                            Command::Alert { args } => {
+                                handle_alert_command(bot, arg, msg, args).await
+                            }
+                            Command::Season { ei } => {
+                                handle_season_command(bot, arg, msg, ei).await
+                            }
                             Command::Help => handle_help(bot, msg).await,
                             Command::Start { args: _ } => {
                                 bot.send_message(
@@ -225,6 +263,7 @@ pub async fn bot_run(
 }
 
 async fn handle_ping(bot: BotType, msg: Message, arg: Arc<NecessaryArg>) -> anyhow::Result<()> {
+    let tz = arg.timezone(msg.chat.id).await;
     bot.send_message(
         msg.chat.id,
         format!(
@@ -232,10 +271,12 @@ async fn handle_ping(bot: BotType, msg: Message, arg: Arc<NecessaryArg>) -> anyh
             Last subscribe query: {last_subscribe_query}",
             id = msg.chat.id.0,
             last_query = replace_all(&timestamp_to_string(
-                LAST_QUERY.load(std::sync::atomic::Ordering::Relaxed) as i64
+                LAST_QUERY.load(std::sync::atomic::Ordering::Relaxed) as i64,
+                tz
             )),
             last_subscribe_query = replace_all(&timestamp_to_string(
-                LAST_SUBSCRIBE_QUERY.load(std::sync::atomic::Ordering::Relaxed) as i64
+                LAST_SUBSCRIBE_QUERY.load(std::sync::atomic::Ordering::Relaxed) as i64,
+                tz
             )),
             check_period = CHECK_PERIOD.get().unwrap(),
             fetch_period = FETCH_PERIOD.get().unwrap(),
@@ -255,12 +296,26 @@ async fn handle_help(bot: BotType, msg: Message) -> anyhow::Result<()> {
     /recent Display recent 1 hour land missions\\.\n\
     /remove `\\<EI\\>` Remove your account from this bot\\.\n\
     /epic\\_export `\\<contract-id\\>` `\\<room-id\\>` `\\<user-uuid\\>`\n\
+    /timezone `\\[IANA name\\|reset\\]` Show or set timezone used in messages\\.\n\
+    /reminder `\\[minutes\\.\\.\\.\\|off\\]` Remind before spaceship landing, e\\.g\\. `/reminder 10 1`\\.\n\
+    /quiet `\\[HH:MM HH:MM\\|off\\]` Hold notifications during quiet hours and send them when it ends\\.\n\
+    /idle `\\[minutes\\|off\\]` Notify when mission slot stays empty for a while\\.\n\
+    /fuel Show fuel tank of your accounts\\.\n\
+    /fuel alert `\\<ship\\> \\<short\\|long\\|epic\\>\\|off` Notify when tank has enough fuel for this mission\\.\n\
+    /profile `\\[account\\]` Show player profile from last backup\\.\n\
+    /growth `\\[account\\] \\[days\\]` Show earning bonus growth, default 7 days\\.\n\
+    /alert `\\[piggy\\|gift\\|announce on\\|off\\]` Notify when piggy bank is full, daily gift is not collected, or new contract and event is available\\.\n\
+    /season `\\<EI\\>` Show contract season goals and progress\\.\n\
     Contract rated:\n\
     `/contract list` List your recent contracts, only available when contract tracker enabled\\.\n\
     `/contract calc \\<EI\\> \\<contract\\-id\\>` Calculate user's contract score\\.\n\
     `/contract room \\<contract\\-id\\> \\<room\\-id\\> \\[detail\\]` Calculate contract score by specify room ID\\.\n\
     `/contract list-user \\<contract\\-id\\> \\<room\\-id\\>` List contract users by specify room ID\\.\n\
-    `/contract enable\\|disable \\<EI\\>` Enable / Disable contract tracker \\(After add to bot\\)\\.\n\n\
+    `/contract enable\\|disable \\<EI\\>` Enable / Disable contract tracker \\(After add to bot\\)\\.\n\
+    `/contract history \\<EI\\> \\[page\\]` Show archived contracts with evaluation\\.\n\
+    `/contract summary \\<EI\\>` Show average CS per grade, best and worst contracts\\.\n\
+    `/contract buffs \\<contract\\-id\\> \\<room\\-id\\>` Show the latest buff changes of each member and their average buff\\.\n\
+    `/contract whatif \\<contract\\-id\\> \\<room\\-id\\> \\<change\\>\\[, \\<change\\>\\]` Simulate completion and CS after changes, change can be `elr \\+\\<rate\\> \\<member\\>`, `elr x\\<multiplier\\> \\<member\\>`, `boost` \\(everyone fills habs\\) or `leave \\<member\\>`\\.\n\n\
     Note:\n\
     `\\[\\.\\.\\.\\]` means optional string\\.
     ").await?;
@@ -1,7 +1,11 @@
 use base64::Engine;
 use chrono::TimeDelta;
+use chrono_tz::Tz;
 use itertools::Itertools as _;
-use std::sync::{Arc, LazyLock};
+use std::{
+    collections::HashMap,
+    sync::{Arc, LazyLock},
+};
 
 use teloxide::{
     payloads::{EditMessageTextSetters, SendMessageSetters},
@@ -12,14 +16,23 @@ use teloxide::{
 use anyhow::anyhow;
 
 use crate::{
+    MAX_MESSAGE_LENGTH,
     bot::replace_all,
-    egg::{decode_and_calc_score, decode_coop_status, encode_to_byte, query_coop_status},
+    database::types::{ContractHistory, ContractSpec},
+    egg::{
+        CoopResult, TokenTransfer, WhatIfChange, coop_start_time, decode_and_calc_score,
+        decode_and_simulate, decode_backup, decode_buff_timeline, decode_coop_status,
+        encode_to_byte, grade_to_str, query_coop_status, query_periodicals,
+    },
     functions::build_reqwest_client,
     types::{BASE64, fmt_time_delta_short, return_tf_emoji, timestamp_to_string},
 };
 
 use super::{BotType, EI_CHECKER_RE, SPACE_RE, arg::NecessaryArg, functions::link_preview_options};
 
+const CONTRACT_HISTORY_PAGE_SIZE: usize = 10;
+const BUFF_TIMELINE_CHANGES: usize = 3;
+
 pub(super) static COOP_ID_RE: LazyLock<regex::Regex> =
     LazyLock::new(|| regex::Regex::new(r"^[\w]+(-[\w\d]+)*$").unwrap());
 pub(super) static ROOM_RE: LazyLock<regex::Regex> =
@@ -59,6 +72,24 @@ pub(super) enum ContractCommand {
         room: String,
         delete: bool,
     },
+    History {
+        ei: String,
+        page: usize,
+    },
+    Summary {
+        ei: String,
+    },
+    Buffs {
+        id: String,
+        room: String,
+        // Set when opened from calc, room is resolved from the account and back returns to calc
+        ei: Option<String>,
+    },
+    WhatIf {
+        id: String,
+        room: String,
+        changes: Vec<WhatIfChange>,
+    },
 }
 
 impl ContractCommand {
@@ -97,11 +128,43 @@ impl ContractCommand {
                         delete: is_delete,
                     })
                 }
+                "history" if EI_CHECKER_RE.is_match(second) => Some(Self::History {
+                    ei: second.into(),
+                    page: third.parse().ok().filter(|page| *page > 0)?,
+                }),
+                "whatif" if COOP_ID_RE.is_match(second) && ROOM_RE.is_match(third) => {
+                    Some(Self::WhatIf {
+                        id: second.into(),
+                        room: third.into(),
+                        changes: WhatIfChange::parse_list(forth)?,
+                    })
+                }
+                "buffs" if COOP_ID_RE.is_match(second) && EI_CHECKER_RE.is_match(third) => {
+                    Some(Self::Buffs {
+                        id: second.into(),
+                        room: String::new(),
+                        ei: Some(third.into()),
+                    })
+                }
+                "buffs" if COOP_ID_RE.is_match(second) && ROOM_RE.is_match(third) => {
+                    Some(Self::Buffs {
+                        id: second.into(),
+                        room: third.into(),
+                        ei: None,
+                    })
+                }
                 _ => None,
             }
         } else {
             match first {
                 "list" if EI_CHECKER_RE.is_match(second) => Some(Self::List { ei: second.into() }),
+                "history" if EI_CHECKER_RE.is_match(second) => Some(Self::History {
+                    ei: second.into(),
+                    page: 1,
+                }),
+                "summary" if EI_CHECKER_RE.is_match(second) => {
+                    Some(Self::Summary { ei: second.into() })
+                }
                 "enable" | "disable" => {
                     if EI_CHECKER_RE.is_match(second) {
                         Some(Self::Control {
@@ -130,23 +193,54 @@ impl ContractCommand {
         }
     }
 
-    fn keyboard(&self, detail: bool) -> InlineKeyboardMarkup {
+    fn keyboard(&self, detail: bool, room: &str) -> InlineKeyboardMarkup {
         let detail = if detail { " d" } else { "" };
         InlineKeyboardMarkup::new(match &self {
-            ContractCommand::Calc { ei, id, .. } => [[
-                InlineKeyboardButton::callback("Refresh", format!("c calc {ei} {id}{detail}")),
-                InlineKeyboardButton::callback(
-                    "Refresh inline",
-                    format!("c-i calc {ei} {id}{detail}"),
-                ),
-            ]],
-            ContractCommand::CalcRoom { id, room, .. } => [[
-                InlineKeyboardButton::callback("Refresh", format!("c room {id} {room}{detail}")),
-                InlineKeyboardButton::callback(
-                    "Refresh inline",
-                    format!("c-i room {id} {room}{detail}"),
-                ),
-            ]],
+            ContractCommand::Calc { ei, id, .. } => [
+                vec![
+                    InlineKeyboardButton::callback("Refresh", format!("c calc {ei} {id}{detail}")),
+                    InlineKeyboardButton::callback(
+                        "Refresh inline",
+                        format!("c-i calc {ei} {id}{detail}"),
+                    ),
+                ],
+                vec![InlineKeyboardButton::callback(
+                    "Buff timeline",
+                    format!("c-i buffs {id} {ei}"),
+                )],
+            ],
+            ContractCommand::CalcRoom { id, room, .. } => [
+                vec![
+                    InlineKeyboardButton::callback(
+                        "Refresh",
+                        format!("c room {id} {room}{detail}"),
+                    ),
+                    InlineKeyboardButton::callback(
+                        "Refresh inline",
+                        format!("c-i room {id} {room}{detail}"),
+                    ),
+                ],
+                vec![InlineKeyboardButton::callback(
+                    "Buff timeline",
+                    format!("c-i buffs {id} {room}"),
+                )],
+            ],
+            ContractCommand::Buffs { id, ei, .. } => {
+                let (refresh, back) = match ei {
+                    Some(ei) => (
+                        format!("c-i buffs {id} {ei}"),
+                        format!("c-i calc {ei} {id}"),
+                    ),
+                    None => (
+                        format!("c-i buffs {id} {room}"),
+                        format!("c-i room {id} {room}"),
+                    ),
+                };
+                [
+                    vec![InlineKeyboardButton::callback("Refresh", refresh)],
+                    vec![InlineKeyboardButton::callback("Back", back)],
+                ]
+            }
             _ => unreachable!(),
         })
     }
@@ -180,7 +274,179 @@ pub(super) async fn route_contract_command(
         ContractCommand::ListUsers { id, room } => {
             handle_list_contract_users(bot, chat_id, arg, id, room).await
         }
+        ContractCommand::History { ei, page } => {
+            handle_contract_history(bot, arg, chat_id, message_id, ei, page, inline).await
+        }
+        ContractCommand::Summary { ei } => handle_contract_summary(bot, arg, chat_id, ei).await,
+        ContractCommand::Buffs { .. } => {
+            handle_buff_timeline(bot, arg, chat_id, message_id, &cmd, inline).await
+        }
+        ContractCommand::WhatIf { id, room, changes } => {
+            handle_whatif(bot, arg, chat_id, id, room, changes).await
+        }
+    }
+}
+
+async fn check_account_permission(
+    arg: &NecessaryArg,
+    chat_id: ChatId,
+    ei: &str,
+) -> anyhow::Result<bool> {
+    Ok(arg.check_admin(chat_id)
+        || arg
+            .database()
+            .account_query(Some(chat_id.0))
+            .await
+            .ok_or_else(|| anyhow!("Query user error"))?
+            .iter()
+            .any(|x| x.ei().eq(ei)))
+}
+
+fn history_line(history: &ContractHistory, tz: Tz) -> String {
+    let issues = history.issues();
+    format!(
+        "`{}` `{}` {} {}\nCS: {} \\({}{}\\), contribution: {}, completion: {}%{}",
+        replace_all(history.id()),
+        replace_all(history.room()),
+        grade_to_str(history.grade()),
+        replace_all(&timestamp_to_string(history.timestamp() as i64, tz)),
+        history.cxp() as i64,
+        if history.cxp_change() >= 0.0 {
+            "\\+"
+        } else {
+            "\\-"
+        },
+        history.cxp_change().abs() as i64,
+        replace_all(&format!("{:.2}", history.contribution_ratio())),
+        replace_all(&format!("{:.1}", history.completion_percent() * 100.0)),
+        if issues.is_empty() {
+            String::new()
+        } else {
+            format!(" ⚠️ {}", replace_all(&issues.join(", ")))
+        }
+    )
+}
+
+async fn handle_contract_history(
+    bot: BotType,
+    arg: Arc<NecessaryArg>,
+    chat_id: ChatId,
+    message_id: MessageId,
+    ei: String,
+    page: usize,
+    inline: bool,
+) -> anyhow::Result<()> {
+    if !check_account_permission(&arg, chat_id, &ei).await? {
+        bot.send_message(chat_id, "Permission denied").await?;
+        return Ok(());
+    }
+
+    let history = arg
+        .database()
+        .contract_history_query(ei.clone())
+        .await
+        .ok_or_else(|| anyhow!("Query contract history error"))?;
+
+    if history.is_empty() {
+        bot.send_message(chat_id, "Contract history not found")
+            .await?;
+        return Ok(());
     }
+
+    let pages = history.len().div_ceil(CONTRACT_HISTORY_PAGE_SIZE);
+    let page = page.min(pages);
+    let tz = arg.timezone(chat_id).await;
+
+    let text = format!(
+        "{}\n\nPage {page}/{pages}",
+        history
+            .iter()
+            .skip((page - 1) * CONTRACT_HISTORY_PAGE_SIZE)
+            .take(CONTRACT_HISTORY_PAGE_SIZE)
+            .map(|history| history_line(history, tz))
+            .join("\n\n")
+    );
+
+    let keyboard = InlineKeyboardMarkup::new([[
+        (page > 1).then(|| {
+            InlineKeyboardButton::callback("Prev", format!("c-i history {ei} {}", page - 1))
+        }),
+        (page < pages).then(|| {
+            InlineKeyboardButton::callback("Next", format!("c-i history {ei} {}", page + 1))
+        }),
+    ]
+    .into_iter()
+    .flatten()
+    .collect_vec()]);
+
+    if inline {
+        bot.edit_message_text(chat_id, message_id, text)
+            .reply_markup(keyboard)
+            .await?;
+    } else {
+        bot.send_message(chat_id, text)
+            .reply_markup(keyboard)
+            .await?;
+    }
+
+    Ok(())
+}
+
+async fn handle_contract_summary(
+    bot: BotType,
+    arg: Arc<NecessaryArg>,
+    chat_id: ChatId,
+    ei: String,
+) -> anyhow::Result<()> {
+    if !check_account_permission(&arg, chat_id, &ei).await? {
+        bot.send_message(chat_id, "Permission denied").await?;
+        return Ok(());
+    }
+
+    let history = arg
+        .database()
+        .contract_history_query(ei)
+        .await
+        .ok_or_else(|| anyhow!("Query contract history error"))?;
+
+    let (Some(best), Some(worst)) = (
+        history.iter().max_by(|a, b| a.cxp().total_cmp(&b.cxp())),
+        history.iter().min_by(|a, b| a.cxp().total_cmp(&b.cxp())),
+    ) else {
+        bot.send_message(chat_id, "Contract history not found")
+            .await?;
+        return Ok(());
+    };
+
+    let grades = history
+        .iter()
+        .into_group_map_by(|history| history.grade())
+        .into_iter()
+        .sorted_by_key(|(grade, _)| std::cmp::Reverse(*grade))
+        .map(|(grade, items)| {
+            format!(
+                "{}: {} contracts, average CS {}",
+                grade_to_str(grade),
+                items.len(),
+                (items.iter().map(|x| x.cxp()).sum::<f64>() / items.len() as f64) as i64
+            )
+        })
+        .join("\n");
+
+    let tz = arg.timezone(chat_id).await;
+
+    bot.send_message(
+        chat_id,
+        format!(
+            "Total {} contracts\n{grades}\n\n*Best*\n{}\n\n*Worst*\n{}",
+            history.len(),
+            history_line(best, tz),
+            history_line(worst, tz)
+        ),
+    )
+    .await?;
+
+    Ok(())
 }
 
 async fn handle_list_contracts(
@@ -208,16 +474,38 @@ async fn handle_list_contracts(
         .await
         .ok_or_else(|| anyhow!("Query user contract error"))?;
 
+    let tz = arg.timezone(chat_id).await;
+
+    let mut names = HashMap::new();
+    for contract in &contracts {
+        if names.contains_key(contract.id()) {
+            continue;
+        }
+        if let Some(spec) = arg
+            .database()
+            .contract_query_spec(contract.id().to_string())
+            .await
+            .flatten()
+        {
+            names.insert(contract.id().to_string(), spec.name().to_string());
+        }
+    }
+
     let res = contracts
         .into_iter()
         .map(|contract| {
             format!(
-                "`{}` `{}` {} [{}](t.me/{}?start={})[📋](t.me/{}?start={})",
+                "{}`{}` `{}` {} [{}](t.me/{}?start={})[📋](t.me/{}?start={})",
+                names
+                    .get(contract.id())
+                    .filter(|name| name.as_str() != contract.id())
+                    .map(|name| format!("{} ", replace_all(name)))
+                    .unwrap_or_default(),
                 replace_all(contract.id()),
                 replace_all(contract.room()),
                 replace_all(&{
                     if let Some(start_time) = contract.start_time() {
-                        timestamp_to_string(start_time as i64)
+                        timestamp_to_string(start_time as i64, tz)
                     } else {
                         "Unknown".into()
                     }
@@ -273,17 +561,19 @@ pub(super) async fn handle_calc_score(
         _ => unreachable!(),
     };
 
-    match process_calc(arg, event, *detail, inline).await {
-        Ok(res) => {
+    let tz = arg.timezone(chat_id).await;
+
+    match process_calc(arg, event, *detail, inline, tz).await {
+        Ok((res, room)) => {
             if inline {
                 bot.edit_message_text(chat_id, message_id, res)
                     .link_preview_options(link_preview_options(false))
-                    .reply_markup(event.keyboard(*detail))
+                    .reply_markup(event.keyboard(*detail, &room))
                     .await
             } else {
                 bot.send_message(chat_id, res)
                     .link_preview_options(link_preview_options(false))
-                    .reply_markup(event.keyboard(*detail))
+                    .reply_markup(event.keyboard(*detail, &room))
                     .await
             }
         }
@@ -297,29 +587,115 @@ pub(super) async fn handle_calc_score(
     Ok(())
 }
 
+/// Query spec valid at timestamp, fetch it if not stored
+async fn query_contract_spec(
+    arg: &NecessaryArg,
+    contract_id: &str,
+    timestamp: f64,
+) -> anyhow::Result<ContractSpec> {
+    match arg
+        .database()
+        .contract_query_spec_at(contract_id.to_string(), timestamp)
+        .await
+        .ok_or_else(|| anyhow!("Query contract spec error"))?
+    {
+        Some(spec) => Ok(spec),
+        None => fetch_contract_spec(arg, contract_id)
+            .await?
+            .ok_or_else(|| anyhow!("Contract spec not found")),
+    }
+}
+
+/// Query spec valid when the coop started, estimated from its remaining time
+async fn query_coop_spec(
+    arg: &NecessaryArg,
+    contract_id: &str,
+    body: &[u8],
+    timestamp: i64,
+) -> anyhow::Result<ContractSpec> {
+    let spec = query_contract_spec(arg, contract_id, timestamp as f64).await?;
+    let Some(start_time) = decode_coop_status(body, false)
+        .ok()
+        .and_then(|status| coop_start_time(&spec, &status, timestamp))
+    else {
+        return Ok(spec);
+    };
+    query_contract_spec(arg, contract_id, start_time).await
+}
+
+/// Find contract spec from tracked accounts' backup or periodicals, and store it
+async fn fetch_contract_spec(
+    arg: &NecessaryArg,
+    contract_id: &str,
+) -> anyhow::Result<Option<ContractSpec>> {
+    let accounts = arg
+        .database()
+        .account_query(None)
+        .await
+        .ok_or_else(|| anyhow!("Query accounts error"))?;
+
+    for account in &accounts {
+        let Some(cache) = arg
+            .database()
+            .account_cache_query(account.ei().to_string())
+            .await
+            .flatten()
+        else {
+            continue;
+        };
+        let Ok(backup) = decode_backup(cache.cache()) else {
+            continue;
+        };
+        let spec = backup
+            .contracts
+            .iter()
+            .flat_map(|contracts| contracts.contracts.iter().chain(contracts.archive.iter()))
+            .filter_map(|local_contract| local_contract.contract.as_ref())
+            .find(|contract| contract.identifier() == contract_id)
+            .map(ContractSpec::from);
+        if let Some(spec) = spec {
+            arg.database().contract_spec_insert(spec.clone()).await;
+            return Ok(Some(spec));
+        }
+    }
+
+    let Some(account) = accounts.iter().find(|account| !account.disabled()) else {
+        return Ok(None);
+    };
+    let periodicals = query_periodicals(&build_reqwest_client(), account.ei()).await?;
+
+    let specs = periodicals
+        .contracts
+        .iter()
+        .flat_map(|contracts| contracts.contracts.iter())
+        .map(ContractSpec::from)
+        .collect_vec();
+
+    let mut ret = None;
+    for spec in specs {
+        if spec.id() == contract_id {
+            ret = Some(spec.clone());
+        }
+        arg.database().contract_spec_insert(spec).await;
+    }
+    Ok(ret)
+}
+
 async fn process_calc(
     arg: Arc<NecessaryArg>,
     event: &ContractCommand,
     detail: bool,
     inline: bool,
-) -> anyhow::Result<String> {
+    tz: Tz,
+) -> anyhow::Result<(String, String)> {
     let contract_id = match event {
         ContractCommand::Calc { id, .. } | ContractCommand::CalcRoom { id, .. } => id,
         _ => unreachable!(),
     };
 
-    let Some(contract_spec) = arg
-        .database()
-        .contract_query_spec(contract_id.to_string())
-        .await
-        .ok_or_else(|| anyhow!("Query contract spec error"))?
-    else {
-        return Err(anyhow!("Contract spec not found"));
-    };
-
     let current_time = kstool::time::get_current_second() as i64;
 
-    let (timestamp, room, body) = match event {
+    let (timestamp, room, body, start_time, tokens) = match event {
         ContractCommand::Calc { ei, .. } => {
             let Some(user_contract) = arg
                 .database()
@@ -338,53 +714,52 @@ async fn process_calc(
             else {
                 return Err(anyhow!("Contract cache not found"));
             };
+
+            // Tokens given and received are only recorded in the player's own backup
+            let tokens = arg
+                .database()
+                .account_cache_query(ei.to_string())
+                .await
+                .flatten()
+                .and_then(|cache| decode_backup(cache.cache()).ok())
+                .and_then(|backup| TokenTransfer::from_backup(&backup, contract_id));
             (
                 contract_cache.timestamp(),
                 contract_cache.room().to_string(),
                 contract_cache.extract(),
+                user_contract.start_time(),
+                tokens,
             )
         }
         ContractCommand::CalcRoom { room, .. } => {
-            match arg
-                .database()
-                .contract_cache_query(contract_id.to_string(), room.to_string())
-                .await
-                .ok_or_else(|| anyhow!("Query contract cache error"))?
-            {
-                Some(cache) if cache.recent() => {
-                    (cache.timestamp(), cache.room().to_string(), cache.extract())
-                }
-                _ => {
-                    let client = build_reqwest_client();
-                    let raw = query_coop_status(&client, contract_id, room, None).await?;
-
-                    let bytes = encode_to_byte(&raw);
-                    arg.database()
-                        .contract_cache_insert(
-                            contract_id.into(),
-                            room.into(),
-                            bytes.clone(),
-                            raw.cleared_for_exit() || raw.all_members_reporting(),
-                            None,
-                            None,
-                        )
-                        .await;
-                    (current_time, room.clone(), bytes)
-                }
-            }
+            let (timestamp, body) = load_coop_status(&arg, contract_id, room, current_time).await?;
+            (timestamp, room.clone(), body, None, None)
         }
         _ => unreachable!(),
     };
 
-    let Some(score) = decode_and_calc_score(contract_spec, &body, false)?.into_optional() else {
-        return Ok(format!(
-            "`{contract}` \\[`{room_id}`\\]\n\
+    let contract_spec = match start_time {
+        Some(start_time) => query_contract_spec(&arg, contract_id, start_time).await?,
+        None => query_coop_spec(&arg, contract_id, &body, timestamp).await?,
+    };
+
+    let egg = contract_spec.egg_name();
+    let name = contract_spec.name().to_string();
+
+    let Some(score) =
+        decode_and_calc_score(contract_spec, &body, false, tokens, timestamp)?.into_optional()
+    else {
+        return Ok((
+            format!(
+                "*{contract}* \\[`{room_id}`\\]\n\
         \n\
         This contract will not be completed before it expires\\. Check [web](https://eicoop-carpet.netlify.app/{contract_id}/{room}) for more information\\.\n\
         Last refresh: {timestamp}",
-            contract = replace_all(contract_id),
-            room_id = replace_all(&room),
-            timestamp = replace_all(&timestamp_to_string(current_time)),
+                contract = replace_all(&name),
+                room_id = replace_all(&room),
+                timestamp = replace_all(&timestamp_to_string(current_time, tz)),
+            ),
+            room,
         ));
     };
 
@@ -395,6 +770,7 @@ async fn process_calc(
             "Expect complete: {}\n{}",
             replace_all(&timestamp_to_string(
                 current_time + score.expect_finish_time(Some(timestamp)) as i64,
+                tz,
             )),
             if current_time > expect {
                 "⚠️*Warning: The contract will be completed beyond the estimated time\\.*\n"
@@ -416,16 +792,53 @@ async fn process_calc(
         })
         .join("\n");
 
+    let teamwork = if detail {
+        format!(
+            "\nTokens: _{}_\nIncoming chicken runs: {}\nIncoming gifts: {}\n",
+            score.display_tokens(),
+            if score.chicken_runs().is_empty() {
+                "None".into()
+            } else {
+                score
+                    .chicken_runs()
+                    .iter()
+                    .map(|(name, amount)| format!("{} \\({amount}\\)", replace_all(name)))
+                    .join(", ")
+            },
+            if score.gifts().is_empty() {
+                "None".into()
+            } else {
+                score
+                    .gifts()
+                    .iter()
+                    .map(|(name, amount)| format!("{} ×{amount}", replace_all(name)))
+                    .join(", ")
+            },
+        )
+    } else {
+        String::new()
+    };
+
     let result = format!(
-        "[*\\({grade}\\)*](https://eicoop-carpet.netlify.app/{contract_id}/{room}) `{contract}` \\[`{room_id}`\\] {current_status}\n\
+        "[*\\({grade}\\)*](https://eicoop-carpet.netlify.app/{contract_id}/{room}) *{contract}* \\[`{room_id}`\\] {current_status}\n\
+        Egg: _{egg}_{modifiers}\n\
         Target: {amount}/{target} ELR: _{elr}_ Buff: _{buff}_\n\
         Contract timestamp: _{completion_time}_ / _{remain}_ remain\n\
-        {sub_title}\n{users}\n\n\
+        {sub_title}\n{users}\n{teamwork}\n\
         Contract last update: {last_update}\n\
         {msg_update}\
         {footer}",
-        contract = replace_all(contract_id),
+        contract = replace_all(&name),
         room_id = replace_all(&room),
+        egg = replace_all(&egg),
+        modifiers = {
+            let modifiers = score.modifiers();
+            if modifiers.is_empty() {
+                String::new()
+            } else {
+                format!(" Modifiers: _{}_", replace_all(&modifiers.join(", ")))
+            }
+        },
         grade = score.grade_str(),
         current_status = score.emoji(),
         elr = replace_all(&score.total_known_elr()),
@@ -436,23 +849,241 @@ async fn process_calc(
         remain = replace_all(&fmt_time_delta_short(TimeDelta::seconds(
             score.contract_remain_time(Some(timestamp)) as i64
         ))),
-        last_update = replace_all(&timestamp_to_string(timestamp)),
+        last_update = replace_all(&timestamp_to_string(timestamp, tz)),
         msg_update = if inline {
             format!(
                 "Score update timestamp: {}\n",
-                replace_all(&timestamp_to_string(current_time))
+                replace_all(&timestamp_to_string(current_time, tz))
             )
         } else {
             String::new()
         },
         footer = if score.is_finished() && !score.is_cleared() {
-            "This score is included your offline contributions, but not included your teamwork score\\.\n"
+            "This score is included your offline contributions\\.\nScore in parentheses is included your estimated teamwork score, chicken runs and tokens are estimated from what coop status reports\\.\n"
         } else {
-            "This score not included your teamwork score\\."
+            "Score in parentheses is included your estimated teamwork score, chicken runs and tokens are estimated from what coop status reports\\."
         }
     );
 
-    Ok(result)
+    Ok((result, room))
+}
+
+async fn handle_buff_timeline(
+    bot: BotType,
+    arg: Arc<NecessaryArg>,
+    chat_id: ChatId,
+    message_id: MessageId,
+    event: &ContractCommand,
+    inline: bool,
+) -> anyhow::Result<()> {
+    let ContractCommand::Buffs { id, room, ei } = event else {
+        unreachable!()
+    };
+
+    if let Some(ei) = ei
+        && !check_account_permission(&arg, chat_id, ei).await?
+    {
+        bot.send_message(chat_id, "Permission denied").await?;
+        return Ok(());
+    }
+
+    let room = match ei {
+        Some(ei) => arg
+            .database()
+            .contract_query_single(id.to_string(), ei.to_string())
+            .await
+            .ok_or_else(|| anyhow!("Query user contract room error"))?
+            .ok_or_else(|| anyhow!("User room not found"))?
+            .room()
+            .to_string(),
+        None => room.clone(),
+    };
+
+    let current_time = kstool::time::get_current_second() as i64;
+    let (timestamp, body) = load_coop_status(&arg, id, &room, current_time).await?;
+    let tz = arg.timezone(chat_id).await;
+    let timeline = decode_buff_timeline(&body, false, timestamp)?;
+
+    // Only the latest changes of each member, fall back to averages if still too long
+    let render = |limit: usize| {
+        let members = timeline
+            .iter()
+            .map(|member| {
+                let (skipped, changes) = member.recent_changes(limit);
+                let mut lines = vec![format!(
+                    "*{}* _Average_ E: {}%, L: {}%",
+                    replace_all(member.username()),
+                    member.average().0 as i64,
+                    member.average().1 as i64,
+                )];
+                if skipped > 0 {
+                    lines.push(format!("_{skipped} earlier changes_"));
+                }
+                lines.extend(changes.iter().map(|(time, e, l)| {
+                    format!(
+                        "{} E: {}%, L: {}%",
+                        replace_all(&timestamp_to_string(*time as i64, tz)),
+                        e.round() as i64,
+                        l.round() as i64,
+                    )
+                }));
+                lines.join("\n")
+            })
+            .join("\n\n");
+
+        format!(
+            "`{}` \\[`{}`\\] buff timeline\n\n{members}\n\nContract last update: {}",
+            replace_all(id),
+            replace_all(&room),
+            replace_all(&timestamp_to_string(timestamp, tz)),
+        )
+    };
+    let mut text = render(BUFF_TIMELINE_CHANGES);
+    if text.chars().count() > MAX_MESSAGE_LENGTH {
+        text = render(0);
+    }
+
+    if inline {
+        bot.edit_message_text(chat_id, message_id, text)
+            .reply_markup(event.keyboard(false, &room))
+            .await?;
+    } else {
+        bot.send_message(chat_id, text)
+            .reply_markup(event.keyboard(false, &room))
+            .await?;
+    }
+
+    Ok(())
+}
+
+fn whatif_completion(result: &CoopResult, timestamp: i64, tz: Tz) -> String {
+    match result {
+        CoopResult::Normal(score) if score.is_finished() => "Completed".into(),
+        CoopResult::Normal(score) => format!(
+            "{} \\(_{}_\\)",
+            replace_all(&timestamp_to_string(
+                kstool::time::get_current_second() as i64
+                    + score.expect_finish_time(Some(timestamp)) as i64,
+                tz
+            )),
+            fmt_time_delta_short(TimeDelta::seconds(score.completion_time() as i64))
+        ),
+        CoopResult::OutOfTime(_) => "Will not complete before it expires".into(),
+    }
+}
+
+fn whatif_member_score(result: &CoopResult, username: &str) -> String {
+    match result {
+        CoopResult::Normal(score) => score
+            .member()
+            .iter()
+            .find(|member| member.username() == username)
+            .map(|member| {
+                format!(
+                    "{} \\({}\\)",
+                    member.score() as i64,
+                    member.teamwork_score() as i64
+                )
+            })
+            .unwrap_or_else(|| "left".into()),
+        CoopResult::OutOfTime(_) => "N/A".into(),
+    }
+}
+
+async fn handle_whatif(
+    bot: BotType,
+    arg: Arc<NecessaryArg>,
+    chat_id: ChatId,
+    id: String,
+    room: String,
+    changes: Vec<WhatIfChange>,
+) -> anyhow::Result<()> {
+    let current_time = kstool::time::get_current_second() as i64;
+    let (timestamp, body) = load_coop_status(&arg, &id, &room, current_time).await?;
+    let spec = query_coop_spec(&arg, &id, &body, timestamp).await?;
+    let name = spec.name().to_string();
+
+    let (before, after) = match decode_and_simulate(spec, &body, false, &changes, timestamp) {
+        Ok(ret) => ret,
+        Err(e) => {
+            bot.send_message(chat_id, replace_all(&e.to_string()))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let tz = arg.timezone(chat_id).await;
+
+    let members = match &before {
+        CoopResult::Normal(score) => score
+            .member()
+            .iter()
+            .map(|member| {
+                format!(
+                    "*{}* {} → {}",
+                    replace_all(member.username()),
+                    whatif_member_score(&before, member.username()),
+                    whatif_member_score(&after, member.username()),
+                )
+            })
+            .join("\n"),
+        CoopResult::OutOfTime(_) => String::new(),
+    };
+
+    bot.send_message(
+        chat_id,
+        format!(
+            "*{}* \\[`{}`\\] what if\n{}\n\n\
+            Before: {}\n\
+            After: {}\n\n\
+            {members}",
+            replace_all(&name),
+            replace_all(&room),
+            changes
+                .iter()
+                .map(|change| format!("\\- {}", replace_all(&change.to_string())))
+                .join("\n"),
+            whatif_completion(&before, timestamp, tz),
+            whatif_completion(&after, timestamp, tz),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Read coop status from cache, or query and cache it if not recent
+async fn load_coop_status(
+    arg: &NecessaryArg,
+    contract_id: &str,
+    room: &str,
+    current_time: i64,
+) -> anyhow::Result<(i64, Vec<u8>)> {
+    match arg
+        .database()
+        .contract_cache_query(contract_id.to_string(), room.to_string())
+        .await
+        .ok_or_else(|| anyhow!("Query contract cache error"))?
+    {
+        Some(cache) if cache.recent() => Ok((cache.timestamp(), cache.extract())),
+        _ => {
+            let client = build_reqwest_client();
+            let raw = query_coop_status(&client, contract_id, room, None).await?;
+
+            let bytes = encode_to_byte(&raw);
+            arg.database()
+                .contract_cache_insert(
+                    contract_id.into(),
+                    room.into(),
+                    bytes.clone(),
+                    raw.cleared_for_exit() || raw.all_members_reporting(),
+                    None,
+                    None,
+                )
+                .await;
+            Ok((current_time, bytes))
+        }
+    }
 }
 
 async fn handle_enable_contract_tracker(
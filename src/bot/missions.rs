@@ -107,10 +107,12 @@ pub(super) async fn handle_list_command(
         return Ok(());
     }
 
+    let tz = arg.timezone(msg.chat.id).await;
+
     bot.send_message(
         msg.chat.id,
         ret.into_iter()
-            .map(|s| s.line(arg.username(), show_ei))
+            .map(|s| s.line(arg.username(), show_ei, tz))
             .join("\n"),
     )
     .await?;
@@ -158,13 +160,20 @@ pub(super) async fn handle_missions_command(
         return Ok(());
     }
 
+    let tz = arg.timezone(msg.chat.id).await;
+
     let text = ret
         .into_iter()
         .filter(|(_, spaceships)| !spaceships.is_empty())
         .map(|(player, spaceships)| {
             format!(
-                "*{}*:\n{}",
+                "*{}*{}:\n{}",
                 replace_all(player.name()),
+                if player.is_stale() {
+                    " ⚠️ _backup is outdated_"
+                } else {
+                    ""
+                },
                 iter_spaceships(spaceships, recent)
                     .map(|s| {
                         let delta = s.calc_time(&msg.date);
@@ -174,10 +183,12 @@ pub(super) async fn handle_missions_command(
                             format!(" {} left", delta)
                         };
                         format!(
-                            "{} \\({}\\) {} {}{delta}",
+                            "{} \\({}\\) {} {} {} {}{delta}",
                             replace_all(s.name()),
                             s.duration_type(),
-                            replace_all(&timestamp_to_string(s.land())),
+                            replace_all(&s.description()),
+                            s.status(),
+                            replace_all(&timestamp_to_string(s.land(), tz)),
                             return_tf_emoji(s.notified())
                         )
                     })
@@ -0,0 +1,367 @@
+use std::sync::Arc;
+
+use chrono::{NaiveTime, Timelike as _};
+use chrono_tz::Tz;
+use itertools::Itertools as _;
+use teloxide::{prelude::Requester as _, types::Message};
+
+use crate::database::types::UserSetting;
+use crate::{
+    MAX_IDLE_THRESHOLD, MAX_REMINDER_MINUTES, MAX_REMINDERS,
+    types::{return_tf_emoji, timestamp_to_string},
+};
+
+use super::functions::replace_all;
+use super::{BotType, arg::NecessaryArg};
+
+pub(super) async fn handle_timezone_command(
+    bot: BotType,
+    arg: Arc<NecessaryArg>,
+    msg: Message,
+    timezone: String,
+) -> anyhow::Result<()> {
+    let timezone = timezone.trim();
+
+    if timezone.is_empty() {
+        let tz = arg.timezone(msg.chat.id).await;
+        bot.send_message(
+            msg.chat.id,
+            format!(
+                "Current timezone: `{}`\nLocal time: {}",
+                replace_all(tz.name()),
+                replace_all(&timestamp_to_string(
+                    kstool::time::get_current_second() as i64,
+                    tz
+                ))
+            ),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    if timezone.eq("reset") {
+        arg.database()
+            .user_timezone_update(msg.chat.id.0, None)
+            .await;
+        bot.send_message(msg.chat.id, "Timezone reset to default\\.")
+            .await?;
+        return Ok(());
+    }
+
+    let Ok(tz) = timezone.parse::<Tz>() else {
+        bot.send_message(
+            msg.chat.id,
+            "Unknown timezone, please use IANA timezone name like `Europe/Berlin`\\.",
+        )
+        .await?;
+        return Ok(());
+    };
+
+    arg.database()
+        .user_timezone_update(msg.chat.id.0, Some(tz.name().to_string()))
+        .await;
+
+    bot.send_message(
+        msg.chat.id,
+        format!("Timezone set to `{}`", replace_all(tz.name())),
+    )
+    .await?;
+
+    Ok(())
+}
+
+pub(super) async fn handle_reminder_command(
+    bot: BotType,
+    arg: Arc<NecessaryArg>,
+    msg: Message,
+    minutes: String,
+) -> anyhow::Result<()> {
+    let minutes = minutes.trim();
+
+    if minutes.is_empty() {
+        let reminders = arg
+            .database()
+            .user_setting_query(msg.chat.id.0)
+            .await
+            .map(|setting| setting.reminders())
+            .unwrap_or_default();
+        bot.send_message(
+            msg.chat.id,
+            if reminders.is_empty() {
+                "Landing reminder is disabled\\.".to_string()
+            } else {
+                format!(
+                    "Remind before landing: {} minutes",
+                    reminders.iter().join(", ")
+                )
+            },
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let reminders = if minutes.eq("off") {
+        vec![]
+    } else {
+        let Some(reminders) = minutes
+            .split([' ', ','])
+            .filter(|s| !s.is_empty())
+            .map(|s| {
+                s.parse::<i64>()
+                    .ok()
+                    .filter(|m| (1..=MAX_REMINDER_MINUTES).contains(m))
+            })
+            .collect::<Option<Vec<_>>>()
+        else {
+            bot.send_message(
+                msg.chat.id,
+                format!("Reminder should be minutes between 1 and {MAX_REMINDER_MINUTES}\\."),
+            )
+            .await?;
+            return Ok(());
+        };
+        let reminders = reminders.into_iter().unique().collect_vec();
+        if reminders.len() > MAX_REMINDERS {
+            bot.send_message(
+                msg.chat.id,
+                format!("At most {MAX_REMINDERS} reminders are allowed\\."),
+            )
+            .await?;
+            return Ok(());
+        }
+        reminders
+    };
+
+    arg.database()
+        .user_reminders_update(msg.chat.id.0, reminders.clone())
+        .await;
+
+    bot.send_message(
+        msg.chat.id,
+        if reminders.is_empty() {
+            "Landing reminder disabled\\.".to_string()
+        } else {
+            format!(
+                "Remind before landing set to: {} minutes",
+                reminders.iter().sorted().rev().join(", ")
+            )
+        },
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn fmt_minute_of_day(minute: i64) -> String {
+    format!("{:02}:{:02}", minute / 60, minute % 60)
+}
+
+fn parse_minute_of_day(s: &str) -> Option<i64> {
+    let time = NaiveTime::parse_from_str(s, "%H:%M").ok()?;
+    Some((time.hour() * 60 + time.minute()) as i64)
+}
+
+pub(super) async fn handle_quiet_command(
+    bot: BotType,
+    arg: Arc<NecessaryArg>,
+    msg: Message,
+    window: String,
+) -> anyhow::Result<()> {
+    let window = window.trim();
+
+    if window.is_empty() {
+        let setting = arg
+            .database()
+            .user_setting_query(msg.chat.id.0)
+            .await
+            .unwrap_or_else(|| UserSetting::new(msg.chat.id.0));
+        bot.send_message(
+            msg.chat.id,
+            match setting.quiet() {
+                Some((start, end)) => format!(
+                    "Quiet hours: `{}` \\- `{}` \\({}\\)",
+                    fmt_minute_of_day(start),
+                    fmt_minute_of_day(end),
+                    replace_all(setting.timezone().name())
+                ),
+                None => "Quiet hours is disabled\\.".to_string(),
+            },
+        )
+        .await?;
+        return Ok(());
+    }
+
+    if window.eq("off") {
+        arg.database().user_quiet_update(msg.chat.id.0, None).await;
+        bot.send_message(msg.chat.id, "Quiet hours disabled\\.")
+            .await?;
+        return Ok(());
+    }
+
+    let Some((start, end)) = window.split_once(' ').and_then(|(start, end)| {
+        parse_minute_of_day(start.trim()).zip(parse_minute_of_day(end.trim()))
+    }) else {
+        bot.send_message(msg.chat.id, "Invalid format, usage: `/quiet 23:00 07:30`")
+            .await?;
+        return Ok(());
+    };
+
+    if start == end {
+        bot.send_message(
+            msg.chat.id,
+            "Quiet hours start and end should be different\\.",
+        )
+        .await?;
+        return Ok(());
+    }
+
+    arg.database()
+        .user_quiet_update(msg.chat.id.0, Some((start, end)))
+        .await;
+
+    bot.send_message(
+        msg.chat.id,
+        format!(
+            "Quiet hours set to `{}` \\- `{}` \\({}\\)",
+            fmt_minute_of_day(start),
+            fmt_minute_of_day(end),
+            replace_all(arg.timezone(msg.chat.id).await.name())
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+pub(super) async fn handle_idle_command(
+    bot: BotType,
+    arg: Arc<NecessaryArg>,
+    msg: Message,
+    minutes: String,
+) -> anyhow::Result<()> {
+    let minutes = minutes.trim();
+
+    if minutes.is_empty() {
+        let threshold = arg
+            .database()
+            .user_setting_query(msg.chat.id.0)
+            .await
+            .and_then(|setting| setting.idle_threshold());
+        bot.send_message(
+            msg.chat.id,
+            match threshold {
+                Some(threshold) => {
+                    format!("Notify when mission slot is empty for {threshold} minutes")
+                }
+                None => "Empty mission slot notification is disabled\\.".to_string(),
+            },
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let threshold = if minutes.eq("off") {
+        None
+    } else {
+        let Some(threshold) = minutes
+            .parse::<i64>()
+            .ok()
+            .filter(|m| (1..=MAX_IDLE_THRESHOLD).contains(m))
+        else {
+            bot.send_message(
+                msg.chat.id,
+                format!("Threshold should be minutes between 1 and {MAX_IDLE_THRESHOLD}\\."),
+            )
+            .await?;
+            return Ok(());
+        };
+        Some(threshold)
+    };
+
+    arg.database()
+        .user_idle_threshold_update(msg.chat.id.0, threshold)
+        .await;
+
+    bot.send_message(
+        msg.chat.id,
+        match threshold {
+            Some(threshold) => {
+                format!("Will notify when mission slot is empty for {threshold} minutes")
+            }
+            None => "Empty mission slot notification disabled\\.".to_string(),
+        },
+    )
+    .await?;
+
+    Ok(())
+}
+
+pub(super) async fn handle_alert_command(
+    bot: BotType,
+    arg: Arc<NecessaryArg>,
+    msg: Message,
+    args: String,
+) -> anyhow::Result<()> {
+    let setting = arg
+        .database()
+        .user_setting_query(msg.chat.id.0)
+        .await
+        .unwrap_or_else(|| UserSetting::new(msg.chat.id.0));
+    let (mut piggy_alert, mut gift_alert, mut announce) = (
+        setting.piggy_alert(),
+        setting.gift_alert(),
+        setting.announce(),
+    );
+
+    let args = args.trim();
+    if !args.is_empty() {
+        let Some((kind, enabled)) = args.split_once(' ').and_then(|(kind, enabled)| {
+            match enabled.trim() {
+                "on" => Some(true),
+                "off" => Some(false),
+                _ => None,
+            }
+            .map(|enabled| (kind, enabled))
+        }) else {
+            bot.send_message(msg.chat.id, "Invalid format, usage: `/alert piggy on`")
+                .await?;
+            return Ok(());
+        };
+        match kind {
+            "piggy" => piggy_alert = enabled,
+            "gift" => gift_alert = enabled,
+            "announce" => announce = enabled,
+            _ => {
+                bot.send_message(
+                    msg.chat.id,
+                    "Alert should be `piggy`, `gift` or `announce`\\.",
+                )
+                .await?;
+                return Ok(());
+            }
+        }
+        arg.database()
+            .user_alert_update(msg.chat.id.0, piggy_alert, gift_alert, announce)
+            .await;
+    }
+
+    bot.send_message(
+        msg.chat.id,
+        format!(
+            "Piggy bank full: {}\nDaily gift not collected: {}\nNew contracts and events: {}",
+            return_tf_emoji(piggy_alert),
+            return_tf_emoji(gift_alert),
+            return_tf_emoji(announce)
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+pub(super) mod prelude {
+    pub(in crate::bot) use super::{
+        handle_alert_command, handle_idle_command, handle_quiet_command, handle_reminder_command,
+        handle_timezone_command,
+    };
+}
@@ -1,5 +1,7 @@
 use super::types::*;
 use super::{DBResult, versions::prelude::*};
+use crate::NOTIFICATION_KEEP_PERIOD;
+use crate::egg::types::SpaceShipInfo;
 use futures_util::StreamExt as _;
 use sqlx::{Connection, SqliteConnection, sqlite::SqliteConnectOptions};
 
@@ -89,6 +91,57 @@ impl Database {
                     v7::VERSION => {
                         v8::merge_v7(&mut self.conn).await?;
                     }
+                    v8::VERSION => {
+                        v9::merge_v8(&mut self.conn).await?;
+                    }
+                    v9::VERSION => {
+                        v10::merge_v9(&mut self.conn).await?;
+                    }
+                    v10::VERSION => {
+                        v11::merge_v10(&mut self.conn).await?;
+                    }
+                    v11::VERSION => {
+                        v12::merge_v11(&mut self.conn).await?;
+                    }
+                    v12::VERSION => {
+                        v13::merge_v12(&mut self.conn).await?;
+                    }
+                    v13::VERSION => {
+                        v14::merge_v13(&mut self.conn).await?;
+                    }
+                    v14::VERSION => {
+                        v15::merge_v14(&mut self.conn).await?;
+                    }
+                    v15::VERSION => {
+                        v16::merge_v15(&mut self.conn).await?;
+                    }
+                    v16::VERSION => {
+                        v17::merge_v16(&mut self.conn).await?;
+                    }
+                    v17::VERSION => {
+                        v18::merge_v17(&mut self.conn).await?;
+                    }
+                    v18::VERSION => {
+                        v19::merge_v18(&mut self.conn).await?;
+                    }
+                    v19::VERSION => {
+                        v20::merge_v19(&mut self.conn).await?;
+                    }
+                    v20::VERSION => {
+                        v21::merge_v20(&mut self.conn).await?;
+                    }
+                    v21::VERSION => {
+                        v22::merge_v21(&mut self.conn).await?;
+                    }
+                    v22::VERSION => {
+                        v23::merge_v22(&mut self.conn).await?;
+                    }
+                    v23::VERSION => {
+                        v24::merge_v23(&mut self.conn).await?;
+                    }
+                    v24::VERSION => {
+                        v25::merge_v24(&mut self.conn).await?;
+                    }
                     current::VERSION => break,
                     _ => {
                         panic!("Unknown database version: {version}, exit")
@@ -277,6 +330,24 @@ impl Database {
         Ok(())
     }
 
+    pub async fn set_account_idle(&mut self, ei: &str, idle_since: Option<i64>) -> DBResult<()> {
+        sqlx::query(r#"UPDATE "account" SET "idle_since" = ? WHERE "ei" = ? "#)
+            .bind(idle_since)
+            .bind(ei)
+            .execute(&mut self.conn)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_account_backup_time(&mut self, ei: &str, backup_time: i64) -> DBResult<()> {
+        sqlx::query(r#"UPDATE "account" SET "backup_time" = ? WHERE "ei" = ? "#)
+            .bind(backup_time)
+            .bind(ei)
+            .execute(&mut self.conn)
+            .await?;
+        Ok(())
+    }
+
     pub async fn account_mission_reset(&mut self, ei: &str, limit: usize) -> DBResult<()> {
         for spaceship in self
             .query_spaceship_by_ei(ei)
@@ -284,28 +355,37 @@ impl Database {
             .into_iter()
             .take(limit)
         {
-            sqlx::query(r#"UPDATE "spaceship" SET "notified" = 0 WHERE "id" = ?"#)
-                .bind(spaceship.id())
-                .execute(&mut self.conn)
-                .await?;
+            sqlx::query(
+                r#"UPDATE "spaceship" SET "notified" = 0, "reminded" = NULL WHERE "id" = ?"#,
+            )
+            .bind(spaceship.id())
+            .execute(&mut self.conn)
+            .await?;
         }
         Ok(())
     }
 
-    pub async fn insert_spaceship(
-        &mut self,
-        id: String,
-        name: String,
-        duration: i64,
-        belong: String,
-        land: i64,
-    ) -> DBResult<()> {
-        sqlx::query(r#"INSERT INTO "spaceship" VALUES (?, ?, ?, ?, ?, 0)"#)
-            .bind(id)
-            .bind(name)
-            .bind(duration)
+    pub async fn insert_spaceship(&mut self, belong: String, info: &SpaceShipInfo) -> DBResult<()> {
+        sqlx::query(r#"INSERT INTO "spaceship" VALUES (?, ?, ?, ?, ?, 0, NULL, ?, ?, ?, ?, ?)"#)
+            .bind(info.id())
+            .bind(info.name())
+            .bind(info.duration_type())
             .bind(belong)
-            .bind(land)
+            .bind(info.land())
+            .bind(info.level())
+            .bind(info.capacity())
+            .bind(info.target())
+            .bind(info.status())
+            .bind(info.mission_type())
+            .execute(&mut self.conn)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_spaceship_status(&mut self, id: &str, status: i64) -> DBResult<()> {
+        sqlx::query(r#"UPDATE "spaceship" SET "status" = ? WHERE "id" = ? "#)
+            .bind(status)
+            .bind(id)
             .execute(&mut self.conn)
             .await?;
         Ok(())
@@ -320,6 +400,20 @@ impl Database {
         .await
     }
 
+    pub async fn query_spaceship_upcoming(
+        &mut self,
+        current: i64,
+        deadline: i64,
+    ) -> DBResult<Vec<SpaceShip>> {
+        sqlx::query_as(
+            r#"SELECT * FROM "spaceship" WHERE "land" > ? AND "land" <= ? AND "notified" = 0 ORDER BY "land""#,
+        )
+        .bind(current)
+        .bind(deadline)
+        .fetch_all(&mut self.conn)
+        .await
+    }
+
     pub async fn query_spaceship_by_id(&mut self, identifier: &str) -> DBResult<Option<SpaceShip>> {
         sqlx::query_as(r#"SELECT * FROM "spaceship" WHERE "id" = ?"#)
             .bind(identifier)
@@ -344,6 +438,15 @@ impl Database {
         Ok(())
     }
 
+    pub async fn mark_spaceship_reminded(&mut self, id: &str, reminded: i64) -> DBResult<()> {
+        sqlx::query(r#"UPDATE "spaceship" SET "reminded" = ? WHERE "id" = ? "#)
+            .bind(reminded)
+            .bind(id)
+            .execute(&mut self.conn)
+            .await?;
+        Ok(())
+    }
+
     pub async fn query_single_contract(
         &mut self,
         id: &str,
@@ -441,16 +544,49 @@ impl Database {
         .await
     }
 
+    /// Return latest spec of contract
     pub async fn query_contract_spec(&mut self, id: &str) -> DBResult<Option<ContractSpec>> {
         sqlx::query_as(
             r#"SELECT * FROM "contract"
-            WHERE "id" = ?"#,
+            WHERE "id" = ? ORDER BY "start_time" DESC LIMIT 1"#,
         )
         .bind(id)
         .fetch_optional(&mut self.conn)
         .await
     }
 
+    /// Return spec which is valid at specify timestamp, fallback to latest one
+    pub async fn query_contract_spec_at(
+        &mut self,
+        id: &str,
+        timestamp: f64,
+    ) -> DBResult<Option<ContractSpec>> {
+        let ret = sqlx::query_as(
+            r#"SELECT * FROM "contract"
+            WHERE "id" = ? AND "start_time" <= ? ORDER BY "start_time" DESC LIMIT 1"#,
+        )
+        .bind(id)
+        .bind(timestamp)
+        .fetch_optional(&mut self.conn)
+        .await?;
+        if ret.is_some() {
+            return Ok(ret);
+        }
+        self.query_contract_spec(id).await
+    }
+
+    async fn query_contract_spec_exact(
+        &mut self,
+        id: &str,
+        start_time: f64,
+    ) -> DBResult<Option<ContractSpec>> {
+        sqlx::query_as(r#"SELECT * FROM "contract" WHERE "id" = ? AND "start_time" = ?"#)
+            .bind(id)
+            .bind(start_time)
+            .fetch_optional(&mut self.conn)
+            .await
+    }
+
     pub async fn insert_contract_cache(
         &mut self,
         id: &str,
@@ -471,23 +607,40 @@ impl Database {
         Ok(())
     }
 
-    pub async fn insert_contract_spec(
-        &mut self,
-        id: &str,
-        size: i64,
-        token_time: f64,
-        body: &[u8],
-    ) -> DBResult<()> {
-        sqlx::query(r#"INSERT INTO "contract" VALUES (?, ?, ?, ?)"#)
-            .bind(id)
-            .bind(size)
-            .bind(token_time)
+    pub async fn insert_contract_spec(&mut self, contract_spec: &ContractSpec) -> DBResult<()> {
+        let body = minicbor_serde::to_vec(contract_spec.get_inner()).unwrap();
+        sqlx::query(r#"INSERT INTO "contract" VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)"#)
+            .bind(contract_spec.id())
+            .bind(contract_spec.max_coop_size())
+            .bind(contract_spec.token_time())
             .bind(body)
+            .bind(contract_spec.start_time())
+            .bind(contract_spec.egg())
+            .bind(contract_spec.custom_egg())
+            .bind(contract_spec.name())
+            .bind(contract_spec.expiration_time())
             .execute(&mut self.conn)
             .await?;
         Ok(())
     }
 
+    /// Return true if spec is newly inserted
+    pub async fn insert_contract_spec_if_absent(
+        &mut self,
+        contract_spec: &ContractSpec,
+    ) -> DBResult<bool> {
+        if self
+            .query_contract_spec_exact(contract_spec.id(), contract_spec.start_time())
+            .await?
+            .is_some()
+        {
+            return Ok(false);
+        }
+
+        self.insert_contract_spec(contract_spec).await?;
+        Ok(true)
+    }
+
     pub async fn update_contract_cache(
         &mut self,
         id: &str,
@@ -669,6 +822,297 @@ impl Database {
             .await
     }
 
+    pub async fn query_user_setting(&mut self, id: i64) -> DBResult<Option<UserSetting>> {
+        sqlx::query_as(r#"SELECT * FROM "user_setting" WHERE "id" = ?"#)
+            .bind(id)
+            .fetch_optional(&mut self.conn)
+            .await
+    }
+
+    async fn ensure_user_setting(&mut self, id: i64) -> DBResult<()> {
+        sqlx::query(r#"INSERT OR IGNORE INTO "user_setting" ("id") VALUES (?)"#)
+            .bind(id)
+            .execute(&mut self.conn)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_user_timezone(&mut self, id: i64, timezone: Option<&str>) -> DBResult<()> {
+        self.ensure_user_setting(id).await?;
+        sqlx::query(r#"UPDATE "user_setting" SET "timezone" = ? WHERE "id" = ?"#)
+            .bind(timezone)
+            .bind(id)
+            .execute(&mut self.conn)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_user_reminders(&mut self, id: i64, reminders: &str) -> DBResult<()> {
+        self.ensure_user_setting(id).await?;
+        sqlx::query(r#"UPDATE "user_setting" SET "reminders" = ? WHERE "id" = ?"#)
+            .bind(reminders)
+            .bind(id)
+            .execute(&mut self.conn)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_user_quiet(&mut self, id: i64, quiet: Option<(i64, i64)>) -> DBResult<()> {
+        self.ensure_user_setting(id).await?;
+        sqlx::query(
+            r#"UPDATE "user_setting" SET "quiet_start" = ?, "quiet_end" = ? WHERE "id" = ?"#,
+        )
+        .bind(quiet.map(|(start, _)| start))
+        .bind(quiet.map(|(_, end)| end))
+        .bind(id)
+        .execute(&mut self.conn)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn set_user_idle_threshold(
+        &mut self,
+        id: i64,
+        threshold: Option<i64>,
+    ) -> DBResult<()> {
+        self.ensure_user_setting(id).await?;
+        sqlx::query(r#"UPDATE "user_setting" SET "idle_threshold" = ? WHERE "id" = ?"#)
+            .bind(threshold)
+            .bind(id)
+            .execute(&mut self.conn)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_user_fuel_alert(&mut self, id: i64, fuel_alert: Option<&str>) -> DBResult<()> {
+        self.ensure_user_setting(id).await?;
+        sqlx::query(r#"UPDATE "user_setting" SET "fuel_alert" = ? WHERE "id" = ?"#)
+            .bind(fuel_alert)
+            .bind(id)
+            .execute(&mut self.conn)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_user_alert(
+        &mut self,
+        id: i64,
+        piggy_alert: bool,
+        gift_alert: bool,
+        announce: bool,
+    ) -> DBResult<()> {
+        self.ensure_user_setting(id).await?;
+        sqlx::query(
+            r#"UPDATE "user_setting" SET "piggy_alert" = ?, "gift_alert" = ?, "announce" = ? WHERE "id" = ?"#,
+        )
+        .bind(piggy_alert)
+        .bind(gift_alert)
+        .bind(announce)
+        .bind(id)
+        .execute(&mut self.conn)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn query_announce_users(&mut self) -> DBResult<Vec<UserSetting>> {
+        sqlx::query_as(r#"SELECT * FROM "user_setting" WHERE "announce" = 1"#)
+            .fetch_all(&mut self.conn)
+            .await
+    }
+
+    /// Return true if this periodical item is never seen before
+    pub async fn insert_periodical_seen(&mut self, kind: &str, id: &str) -> DBResult<bool> {
+        Ok(
+            sqlx::query(r#"INSERT OR IGNORE INTO "periodical_seen" VALUES (?, ?, ?)"#)
+                .bind(kind)
+                .bind(id)
+                .bind(kstool::time::get_current_second() as i64)
+                .execute(&mut self.conn)
+                .await?
+                .rows_affected()
+                > 0,
+        )
+    }
+
+    /// Return true if key is never notified to this user before
+    pub async fn update_account_notification(
+        &mut self,
+        user: i64,
+        ei: &str,
+        kind: &str,
+        key: &str,
+    ) -> DBResult<bool> {
+        let current_time = kstool::time::get_current_second() as i64;
+        sqlx::query(r#"DELETE FROM "account_notification" WHERE "timestamp" < ?"#)
+            .bind(current_time - NOTIFICATION_KEEP_PERIOD)
+            .execute(&mut self.conn)
+            .await?;
+        Ok(
+            sqlx::query(r#"INSERT OR IGNORE INTO "account_notification" VALUES (?, ?, ?, ?, ?)"#)
+                .bind(user)
+                .bind(ei)
+                .bind(kind)
+                .bind(key)
+                .bind(current_time)
+                .execute(&mut self.conn)
+                .await?
+                .rows_affected()
+                > 0,
+        )
+    }
+
+    /// Return true if fuel alert state changed from not ready to ready
+    pub async fn update_fuel_notified(
+        &mut self,
+        user: i64,
+        ei: &str,
+        ready: bool,
+    ) -> DBResult<bool> {
+        if !ready {
+            sqlx::query(r#"DELETE FROM "fuel_notified" WHERE "user" = ? AND "ei" = ?"#)
+                .bind(user)
+                .bind(ei)
+                .execute(&mut self.conn)
+                .await?;
+            return Ok(false);
+        }
+        Ok(
+            sqlx::query(r#"INSERT OR IGNORE INTO "fuel_notified" VALUES (?, ?)"#)
+                .bind(user)
+                .bind(ei)
+                .execute(&mut self.conn)
+                .await?
+                .rows_affected()
+                > 0,
+        )
+    }
+
+    pub async fn insert_contract_history(&mut self, history: &ContractHistory) -> DBResult<()> {
+        sqlx::query(
+            r#"INSERT OR REPLACE INTO "contract_history" VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
+        )
+        .bind(history.ei())
+        .bind(history.id())
+        .bind(history.room())
+        .bind(history.grade() as i64)
+        .bind(history.cxp())
+        .bind(history.cxp_change())
+        .bind(history.grade_performance())
+        .bind(history.contribution_ratio())
+        .bind(history.completion_percent())
+        .bind(history.issues().join(","))
+        .bind(history.timestamp())
+        .execute(&mut self.conn)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn query_contract_history(&mut self, ei: &str) -> DBResult<Vec<ContractHistory>> {
+        sqlx::query_as(
+            r#"SELECT * FROM "contract_history" WHERE "ei" = ? ORDER BY "timestamp" DESC"#,
+        )
+        .bind(ei)
+        .fetch_all(&mut self.conn)
+        .await
+    }
+
+    pub async fn insert_contract_season(&mut self, season: &ContractSeason) -> DBResult<()> {
+        sqlx::query(r#"INSERT OR REPLACE INTO "contract_season" VALUES (?, ?, ?, ?, ?, ?)"#)
+            .bind(season.id())
+            .bind(season.name())
+            .bind(season.start_time())
+            .bind(season.raw_info())
+            .bind(season.contracts().join(","))
+            .bind(season.timestamp())
+            .execute(&mut self.conn)
+            .await?;
+        Ok(())
+    }
+
+    /// Latest season by start time
+    pub async fn query_contract_season(&mut self) -> DBResult<Option<ContractSeason>> {
+        sqlx::query_as(r#"SELECT * FROM "contract_season" ORDER BY "start_time" DESC LIMIT 1"#)
+            .fetch_optional(&mut self.conn)
+            .await
+    }
+
+    /// Upsert today's snapshot, return latest snapshot before this one
+    pub async fn insert_account_growth(
+        &mut self,
+        growth: &AccountGrowth,
+    ) -> DBResult<Option<AccountGrowth>> {
+        let previous = sqlx::query_as(
+            r#"SELECT * FROM "account_growth" WHERE "ei" = ? ORDER BY "day" DESC LIMIT 1"#,
+        )
+        .bind(growth.ei())
+        .fetch_optional(&mut self.conn)
+        .await?;
+        sqlx::query(r#"INSERT OR REPLACE INTO "account_growth" VALUES (?, ?, ?, ?, ?)"#)
+            .bind(growth.ei())
+            .bind(growth.day())
+            .bind(growth.soul_eggs())
+            .bind(growth.prophecy_eggs())
+            .bind(growth.earning_bonus())
+            .execute(&mut self.conn)
+            .await?;
+        Ok(previous)
+    }
+
+    pub async fn query_account_growth(
+        &mut self,
+        ei: &str,
+        since: i64,
+    ) -> DBResult<Vec<AccountGrowth>> {
+        sqlx::query_as(
+            r#"SELECT * FROM "account_growth" WHERE "ei" = ? AND "day" >= ? ORDER BY "day""#,
+        )
+        .bind(ei)
+        .bind(since)
+        .fetch_all(&mut self.conn)
+        .await
+    }
+
+    pub async fn insert_pending_message(&mut self, user: i64, text: &str) -> DBResult<()> {
+        sqlx::query(r#"INSERT INTO "pending_message" VALUES (?, ?, ?)"#)
+            .bind(user)
+            .bind(text)
+            .bind(kstool::time::get_current_second() as i64)
+            .execute(&mut self.conn)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn query_pending_users(&mut self) -> DBResult<Vec<i64>> {
+        Ok(
+            sqlx::query_as::<_, (i64,)>(r#"SELECT DISTINCT "user" FROM "pending_message""#)
+                .fetch_all(&mut self.conn)
+                .await?
+                .into_iter()
+                .map(|(user,)| user)
+                .collect(),
+        )
+    }
+
+    pub async fn query_pending_message(&mut self, user: i64) -> DBResult<Vec<PendingMessage>> {
+        sqlx::query_as(
+            r#"SELECT "rowid" AS "id", "user", "text", "timestamp" FROM "pending_message"
+            WHERE "user" = ? ORDER BY "timestamp""#,
+        )
+        .bind(user)
+        .fetch_all(&mut self.conn)
+        .await
+    }
+
+    pub async fn delete_pending_message(&mut self, ids: &[i64]) -> DBResult<()> {
+        for id in ids {
+            sqlx::query(r#"DELETE FROM "pending_message" WHERE "rowid" = ?"#)
+                .bind(id)
+                .execute(&mut self.conn)
+                .await?;
+        }
+        Ok(())
+    }
+
     pub async fn close(self) -> DBResult<()> {
         self.conn.close().await
     }
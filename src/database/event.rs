@@ -1,4 +1,5 @@
 use super::types::*;
+use crate::egg::types::SpaceShipInfo;
 use std::collections::HashMap;
 pub(super) type CheckerArg = ((f64, f64, i64), fn((&[u8], i64), (f64, f64, i64)) -> bool);
 
@@ -60,6 +61,14 @@ pub enum DatabaseEvent {
         ei: String,
         disabled: bool,
     },
+    AccountIdleUpdate {
+        ei: String,
+        idle_since: Option<i64>,
+    },
+    AccountBackupTimeUpdate {
+        ei: String,
+        backup_time: i64,
+    },
     AccountCacheInsert {
         ei: String,
         cache: String,
@@ -70,11 +79,12 @@ pub enum DatabaseEvent {
     },
 
     MissionAdd {
-        id: String,
-        name: String,
-        duration_type: i64,
         belong: String,
-        land: i64
+        info: SpaceShipInfo,
+    },
+    MissionStatusUpdate {
+        id: String,
+        status: i64,
     },
 
     #[ret(Vec<SpaceShip>)]
@@ -89,6 +99,11 @@ pub enum DatabaseEvent {
 
     MissionUpdated { id: String },
 
+    #[ret(Vec<SpaceShip>)]
+    MissionQueryUpcoming { deadline: i64 },
+
+    MissionReminded { id: String, reminded: i64 },
+
     #[ret(Option<SpaceShip>)]
     MissionSingleQuery { identifier: String },
 
@@ -101,6 +116,11 @@ pub enum DatabaseEvent {
     ContractQuerySpec {
         id: String,
     },
+    #[ret(Option<ContractSpec>)]
+    ContractQuerySpecAt {
+        id: String,
+        timestamp: f64,
+    },
     #[ret(Vec<Contract>)]
     AccountQueryContract {
         ei: String,
@@ -148,6 +168,14 @@ pub enum DatabaseEvent {
     },
     #[ret(bool)]
     ContractSpecInsert(ContractSpec),
+    ContractHistoryInsert(ContractHistory),
+    #[ret(Vec<ContractHistory>)]
+    ContractHistoryQuery {
+        ei: String,
+    },
+    ContractSeasonInsert(ContractSeason),
+    #[ret(Option<ContractSeason>)]
+    ContractSeasonQuery,
 
     SubscribeNew(String, String, i64),
     #[ret(Vec<SubscribeInfo>)]
@@ -158,6 +186,79 @@ pub enum DatabaseEvent {
     SubscribeSingleFetch(String, String),
     SubscribeDel(String, String, i64),
 
+    #[ret(UserSetting)]
+    UserSettingQuery {
+        id: i64,
+    },
+    UserTimezoneUpdate {
+        id: i64,
+        timezone: Option<String>,
+    },
+    UserRemindersUpdate {
+        id: i64,
+        reminders: Vec<i64>,
+    },
+    UserQuietUpdate {
+        id: i64,
+        quiet: Option<(i64, i64)>,
+    },
+    UserIdleThresholdUpdate {
+        id: i64,
+        threshold: Option<i64>,
+    },
+    UserFuelAlertUpdate {
+        id: i64,
+        fuel_alert: Option<(i32, i32)>,
+    },
+    UserAlertUpdate {
+        id: i64,
+        piggy_alert: bool,
+        gift_alert: bool,
+        announce: bool,
+    },
+    #[ret(Vec<UserSetting>)]
+    UserAnnounceQuery,
+    #[ret(bool)]
+    PeriodicalSeenInsert {
+        kind: String,
+        id: String,
+    },
+    #[ret(bool)]
+    AccountNotificationUpdate {
+        user: i64,
+        ei: String,
+        kind: String,
+        key: String,
+    },
+    #[ret(bool)]
+    FuelNotifiedUpdate {
+        user: i64,
+        ei: String,
+        ready: bool,
+    },
+
+    #[ret(Option<AccountGrowth>)]
+    AccountGrowthInsert(AccountGrowth),
+    #[ret(Vec<AccountGrowth>)]
+    AccountGrowthQuery {
+        ei: String,
+        since: i64,
+    },
+
+    PendingMessageAdd {
+        user: i64,
+        text: String,
+    },
+    #[ret(Vec<i64>)]
+    PendingMessageUsers,
+    #[ret(Vec<PendingMessage>)]
+    PendingMessageQuery {
+        user: i64,
+    },
+    PendingMessageDelete {
+        ids: Vec<i64>,
+    },
+
     Terminate,
 }
 }
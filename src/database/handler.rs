@@ -1,11 +1,13 @@
 use std::collections::HashMap;
 
+use itertools::Itertools as _;
 use log::error;
 
 use super::{
     DBResult,
     context::Database,
     event::{DatabaseEvent, DatabaseEventReceiver, DatabaseHelper},
+    types::UserSetting,
 };
 
 pub struct DatabaseHandle {
@@ -54,16 +56,11 @@ impl DatabaseHandle {
                     .set_account_status(&ei, kstool::time::get_current_second() as i64, disabled)
                     .await?;
             }
-            DatabaseEvent::MissionAdd {
-                belong,
-                name,
-                duration_type,
-                id,
-                land,
-            } => {
-                database
-                    .insert_spaceship(id, name, duration_type, belong, land)
-                    .await?;
+            DatabaseEvent::MissionAdd { belong, info } => {
+                database.insert_spaceship(belong, &info).await?;
+            }
+            DatabaseEvent::MissionStatusUpdate { id, status } => {
+                database.set_spaceship_status(&id, status).await?;
             }
             DatabaseEvent::MissionQuery {
                 deadline,
@@ -85,6 +82,18 @@ impl DatabaseHandle {
             DatabaseEvent::MissionUpdated { id } => {
                 database.mark_spaceship(&id).await?;
             }
+            DatabaseEvent::MissionQueryUpcoming {
+                deadline,
+                __private_sender,
+            } => {
+                let current = kstool::time::get_current_second() as i64;
+                __private_sender
+                    .send(database.query_spaceship_upcoming(current, deadline).await?)
+                    .ok();
+            }
+            DatabaseEvent::MissionReminded { id, reminded } => {
+                database.mark_spaceship_reminded(&id, reminded).await?;
+            }
             DatabaseEvent::AccountNameUpdate { ei, name } => {
                 database.set_account_nickname(&ei, &name).await?;
             }
@@ -139,6 +148,12 @@ impl DatabaseHandle {
             DatabaseEvent::AccountStatusReset { ei, disabled } => {
                 database.account_status_reset(&ei, disabled).await?;
             }
+            DatabaseEvent::AccountIdleUpdate { ei, idle_since } => {
+                database.set_account_idle(&ei, idle_since).await?;
+            }
+            DatabaseEvent::AccountBackupTimeUpdate { ei, backup_time } => {
+                database.set_account_backup_time(&ei, backup_time).await?;
+            }
             DatabaseEvent::AccountCacheInsert { ei, cache } => {
                 database.insert_account_cache(&ei, cache).await?;
             }
@@ -191,26 +206,13 @@ impl DatabaseHandle {
                 }
             }
             DatabaseEvent::ContractSpecInsert(contract_spec, sender) => {
-                if database
-                    .query_contract_spec(contract_spec.id())
-                    .await?
-                    .is_some()
-                {
-                    sender.send(false).ok();
-                    return Ok(());
-                }
-
-                let id = contract_spec.id().to_string();
-                let body = minicbor_serde::to_vec(contract_spec.get_inner()).unwrap();
-                database
-                    .insert_contract_spec(
-                        &id,
-                        contract_spec.max_coop_size(),
-                        contract_spec.token_time(),
-                        &body,
+                sender
+                    .send(
+                        database
+                            .insert_contract_spec_if_absent(&contract_spec)
+                            .await?,
                     )
-                    .await?;
-                sender.send(true).ok();
+                    .ok();
             }
             DatabaseEvent::ContractQuerySingle {
                 id,
@@ -270,6 +272,15 @@ impl DatabaseHandle {
                     .send(database.query_contract_spec(&id).await?)
                     .ok();
             }
+            DatabaseEvent::ContractQuerySpecAt {
+                id,
+                timestamp,
+                __private_sender,
+            } => {
+                __private_sender
+                    .send(database.query_contract_spec_at(&id, timestamp).await?)
+                    .ok();
+            }
             DatabaseEvent::ContractCacheQuery {
                 id,
                 room,
@@ -315,6 +326,25 @@ impl DatabaseHandle {
                     .modify_subscribe(&contract, &room, user, true)
                     .await?;
             }
+            DatabaseEvent::ContractHistoryInsert(history) => {
+                database.insert_contract_history(&history).await?;
+            }
+            DatabaseEvent::ContractHistoryQuery {
+                ei,
+                __private_sender,
+            } => {
+                __private_sender
+                    .send(database.query_contract_history(&ei).await?)
+                    .ok();
+            }
+            DatabaseEvent::ContractSeasonInsert(season) => {
+                database.insert_contract_season(&season).await?;
+            }
+            DatabaseEvent::ContractSeasonQuery(__private_sender) => {
+                __private_sender
+                    .send(database.query_contract_season().await?)
+                    .ok();
+            }
             DatabaseEvent::SubscribeNew(contract, room, user) => {
                 database
                     .modify_subscribe(&contract, &room, user, false)
@@ -330,6 +360,125 @@ impl DatabaseHandle {
             DatabaseEvent::SubscribeNotified(contract, room) => {
                 database.update_subscribe_notified(&contract, &room).await?;
             }
+            DatabaseEvent::UserSettingQuery {
+                id,
+                __private_sender,
+            } => {
+                __private_sender
+                    .send(
+                        database
+                            .query_user_setting(id)
+                            .await?
+                            .unwrap_or_else(|| UserSetting::new(id)),
+                    )
+                    .ok();
+            }
+            DatabaseEvent::UserTimezoneUpdate { id, timezone } => {
+                database.set_user_timezone(id, timezone.as_deref()).await?;
+            }
+            DatabaseEvent::UserRemindersUpdate { id, reminders } => {
+                database
+                    .set_user_reminders(id, &reminders.iter().join(","))
+                    .await?;
+            }
+            DatabaseEvent::UserQuietUpdate { id, quiet } => {
+                database.set_user_quiet(id, quiet).await?;
+            }
+            DatabaseEvent::UserIdleThresholdUpdate { id, threshold } => {
+                database.set_user_idle_threshold(id, threshold).await?;
+            }
+            DatabaseEvent::UserFuelAlertUpdate { id, fuel_alert } => {
+                database
+                    .set_user_fuel_alert(
+                        id,
+                        fuel_alert
+                            .map(|(ship, duration)| format!("{ship}:{duration}"))
+                            .as_deref(),
+                    )
+                    .await?;
+            }
+            DatabaseEvent::UserAlertUpdate {
+                id,
+                piggy_alert,
+                gift_alert,
+                announce,
+            } => {
+                database
+                    .set_user_alert(id, piggy_alert, gift_alert, announce)
+                    .await?;
+            }
+            DatabaseEvent::UserAnnounceQuery(__private_sender) => {
+                __private_sender
+                    .send(database.query_announce_users().await?)
+                    .ok();
+            }
+            DatabaseEvent::PeriodicalSeenInsert {
+                kind,
+                id,
+                __private_sender,
+            } => {
+                __private_sender
+                    .send(database.insert_periodical_seen(&kind, &id).await?)
+                    .ok();
+            }
+            DatabaseEvent::AccountNotificationUpdate {
+                user,
+                ei,
+                kind,
+                key,
+                __private_sender,
+            } => {
+                __private_sender
+                    .send(
+                        database
+                            .update_account_notification(user, &ei, &kind, &key)
+                            .await?,
+                    )
+                    .ok();
+            }
+            DatabaseEvent::FuelNotifiedUpdate {
+                user,
+                ei,
+                ready,
+                __private_sender,
+            } => {
+                __private_sender
+                    .send(database.update_fuel_notified(user, &ei, ready).await?)
+                    .ok();
+            }
+            DatabaseEvent::AccountGrowthInsert(growth, sender) => {
+                sender
+                    .send(database.insert_account_growth(&growth).await?)
+                    .ok();
+            }
+            DatabaseEvent::AccountGrowthQuery {
+                ei,
+                since,
+                __private_sender,
+            } => {
+                __private_sender
+                    .send(database.query_account_growth(&ei, since).await?)
+                    .ok();
+            }
+            DatabaseEvent::PendingMessageAdd { user, text } => {
+                database.insert_pending_message(user, &text).await?;
+            }
+            DatabaseEvent::PendingMessageUsers(__private_sender) => {
+                __private_sender
+                    .send(database.query_pending_users().await?)
+                    .ok();
+            }
+            DatabaseEvent::PendingMessageQuery {
+                user,
+                __private_sender,
+            } => {
+                __private_sender
+                    .send(database.query_pending_message(user).await?)
+                    .ok();
+            }
+            DatabaseEvent::PendingMessageDelete { ids } => {
+                database.delete_pending_message(&ids).await?;
+            }
         }
         Ok(())
     }
@@ -6,16 +6,27 @@ use std::{
 };
 
 use base64::Engine;
-use chrono::DateTime;
+use chrono::{DateTime, Timelike as _};
 use itertools::Itertools as _;
 use rand::distr::{Alphanumeric, SampleString as _};
 use sqlx::{Row, prelude::FromRow, sqlite::SqliteRow};
 use teloxide::types::ChatId;
 
 use crate::{
+    STALE_BACKUP_THRESHOLD,
     bot::replace_all,
-    egg::types::ContractGradeSpec,
-    types::{BASE64, fmt_time_delta, return_tf_emoji, timestamp_to_string},
+    egg::{
+        artifact_name_to_str, egg_name_to_str, encode_to_byte,
+        proto::{
+            ContractEvaluation, ContractSeasonInfo,
+            artifact_spec::Name as ArtifactName,
+            contract::PlayerGrade,
+            contract_evaluation::PoorBehavior,
+            mission_info::{DurationType, MissionType, Spaceship, Status},
+        },
+        types::ContractGradeSpec,
+    },
+    types::{BASE64, DEFAULT_TIMEZONE, fmt_time_delta, return_tf_emoji, timestamp_to_string},
 };
 
 pub static DEFAULT_NICKNAME: LazyLock<String> = LazyLock::new(|| "N/A".into());
@@ -160,6 +171,10 @@ pub struct Account {
     last_fetch: i64,
     contract_trace: bool,
     disabled: bool,
+    #[sqlx(default)]
+    idle_since: Option<i64>,
+    #[sqlx(default)]
+    backup_time: Option<i64>,
 }
 
 impl Account {
@@ -199,16 +214,29 @@ impl Account {
     pub fn contract_trace(&self) -> bool {
         self.contract_trace
     }
-    pub fn line(&self, username: &str, display_ei: bool) -> String {
+
+    /// Since when account has empty mission slot
+    pub fn idle_since(&self) -> Option<i64> {
+        self.idle_since
+    }
+
+    /// Backup is much older than last fetch, mission data may be outdated
+    pub fn is_stale(&self) -> bool {
+        self.backup_time
+            .is_some_and(|backup_time| self.last_fetch - backup_time > STALE_BACKUP_THRESHOLD)
+    }
+
+    pub fn line(&self, username: &str, display_ei: bool, tz: chrono_tz::Tz) -> String {
         format!(
-            "{}*{}* {} {}{}",
+            "{}*{}*{} {} {}{}",
             if display_ei {
                 format!("{} ", self.ei)
             } else {
                 "".into()
             },
             replace_all(self.name()),
-            replace_all(&timestamp_to_string(self.last_fetch)),
+            if self.is_stale() { " ⚠️" } else { "" },
+            replace_all(&timestamp_to_string(self.last_fetch, tz)),
             return_tf_emoji(!self.disabled),
             if self.contract_trace {
                 format!(
@@ -242,6 +270,12 @@ pub struct SpaceShip {
     belong: String,
     land: i64,
     notified: bool,
+    reminded: Option<i64>,
+    level: i64,
+    capacity: i64,
+    target: Option<i64>,
+    status: i64,
+    mission_type: i64,
 }
 
 impl SpaceShip {
@@ -267,6 +301,52 @@ impl SpaceShip {
         }
     }
 
+    pub fn status(&self) -> &'static str {
+        Self::status_to_str(self.status)
+    }
+
+    pub fn status_code(&self) -> i64 {
+        self.status
+    }
+
+    pub fn status_to_str(status: i64) -> &'static str {
+        match Status::try_from(status as i32) {
+            Ok(Status::Fueling) => "Fueling",
+            Ok(Status::PrepareToLaunch) => "Preparing",
+            Ok(Status::Exploring) => "Exploring",
+            Ok(Status::Returned) => "Returned",
+            Ok(Status::Analyzing) => "Analyzing",
+            Ok(Status::Complete) => "Complete",
+            Ok(Status::Archived) => "Archived",
+            Ok(Status::Aborted) => "Aborted",
+            Err(_) => "Unknown",
+        }
+    }
+
+    pub fn target_to_str(target: Option<i64>) -> String {
+        target
+            .and_then(|target| ArtifactName::try_from(target as i32).ok())
+            .map(artifact_name_to_str)
+            .unwrap_or_else(|| "Any".into())
+    }
+
+    /// Level, capacity and target in one line
+    pub fn detail(level: i64, capacity: i64, target: Option<i64>, mission_type: i64) -> String {
+        format!(
+            "{level}★ cap {capacity}, target {}{}",
+            Self::target_to_str(target),
+            if mission_type == MissionType::Virtue as i64 {
+                ", virtue"
+            } else {
+                ""
+            }
+        )
+    }
+
+    pub fn description(&self) -> String {
+        Self::detail(self.level, self.capacity, self.target, self.mission_type)
+    }
+
     pub fn calc_time(&self, input: &DateTime<chrono::Utc>) -> String {
         if self.notified {
             return Default::default();
@@ -279,6 +359,11 @@ impl SpaceShip {
         self.notified
     }
 
+    /// Smallest reminder lead time (in minutes) already sent for this spaceship
+    pub fn reminded(&self) -> Option<i64> {
+        self.reminded
+    }
+
     pub fn name(&self) -> &str {
         &self.name
     }
@@ -295,6 +380,12 @@ impl SpaceShip {
             belong: ei,
             land: land_time,
             notified: false,
+            reminded: None,
+            level: 0,
+            capacity: 0,
+            target: None,
+            status: Status::Exploring as i64,
+            mission_type: MissionType::Standard as i64,
         }
     }
 }
@@ -349,20 +440,36 @@ pub struct ContractSpec {
     max_coop_size: i64,
     token_time: f64,
     spec: HashMap<crate::egg::proto::contract::PlayerGrade, ContractGradeSpec>,
+    start_time: f64,
+    egg: i32,
+    custom_egg: Option<String>,
+    name: String,
+    expiration_time: f64,
 }
 
 impl ContractSpec {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         id: String,
         max_coop_size: i64,
         token_time: f64,
         spec: Vec<ContractGradeSpec>,
+        start_time: f64,
+        egg: i32,
+        custom_egg: Option<String>,
+        name: String,
+        expiration_time: f64,
     ) -> Self {
         Self {
             id,
             max_coop_size,
             token_time,
             spec: spec.into_iter().map(|x| x.into_kv()).collect(),
+            start_time,
+            egg,
+            custom_egg,
+            name,
+            expiration_time,
         }
     }
 
@@ -381,6 +488,64 @@ impl ContractSpec {
     pub fn token_time(&self) -> f64 {
         self.token_time
     }
+
+    /// Since when this spec is offered, rerun contract will have newer one
+    pub fn start_time(&self) -> f64 {
+        self.start_time
+    }
+
+    pub fn egg(&self) -> i32 {
+        self.egg
+    }
+
+    pub fn custom_egg(&self) -> Option<&str> {
+        self.custom_egg.as_deref()
+    }
+
+    /// Contract display name, fallback to identifier if name is not stored
+    pub fn name(&self) -> &str {
+        if self.name.is_empty() {
+            &self.id
+        } else {
+            &self.name
+        }
+    }
+
+    pub fn expiration_time(&self) -> f64 {
+        self.expiration_time
+    }
+
+    pub fn egg_name(&self) -> String {
+        match &self.custom_egg {
+            Some(custom_egg) => custom_egg.clone(),
+            None => crate::egg::proto::Egg::try_from(self.egg)
+                .map(egg_name_to_str)
+                .unwrap_or_else(|_| "Unknown".into()),
+        }
+    }
+}
+
+impl From<&crate::egg::proto::Contract> for ContractSpec {
+    fn from(contract: &crate::egg::proto::Contract) -> Self {
+        Self::new(
+            contract.identifier().to_string(),
+            contract.max_coop_size() as i64,
+            contract.minutes_per_token(),
+            contract
+                .grade_specs
+                .iter()
+                .map(ContractGradeSpec::from)
+                .collect(),
+            contract.start_time(),
+            contract.egg() as i32,
+            contract
+                .custom_egg_id
+                .clone()
+                .filter(|custom_egg| !custom_egg.is_empty()),
+            contract.name().to_string(),
+            contract.expiration_time(),
+        )
+    }
 }
 
 impl Deref for ContractSpec {
@@ -403,6 +568,11 @@ impl FromRow<'_, SqliteRow> for ContractSpec {
                     .unwrap();
                 v.into_iter().map(|x| x.into_kv()).collect()
             },
+            start_time: row.try_get(4)?,
+            egg: row.try_get(5)?,
+            custom_egg: row.try_get(6)?,
+            name: row.try_get(7)?,
+            expiration_time: row.try_get(8)?,
         })
     }
 }
@@ -595,3 +765,364 @@ impl AccountCache {
         self.timestamp
     }
 }
+
+#[derive(Clone, Debug, FromRow)]
+pub struct UserSetting {
+    id: i64,
+    timezone: Option<String>,
+    reminders: String,
+    quiet_start: Option<i64>,
+    quiet_end: Option<i64>,
+    idle_threshold: Option<i64>,
+    fuel_alert: Option<String>,
+    piggy_alert: bool,
+    gift_alert: bool,
+    announce: bool,
+}
+
+impl UserSetting {
+    /// Default setting for user who never changed anything
+    pub fn new(id: i64) -> Self {
+        Self {
+            id,
+            timezone: None,
+            reminders: String::new(),
+            quiet_start: None,
+            quiet_end: None,
+            idle_threshold: None,
+            fuel_alert: None,
+            piggy_alert: false,
+            gift_alert: false,
+            announce: false,
+        }
+    }
+
+    pub fn id(&self) -> i64 {
+        self.id
+    }
+
+    pub fn timezone(&self) -> chrono_tz::Tz {
+        self.timezone
+            .as_ref()
+            .and_then(|tz| {
+                tz.parse()
+                    .inspect_err(|e| log::warn!("Parse timezone {tz:?} failure: {e:?}"))
+                    .ok()
+            })
+            .unwrap_or(DEFAULT_TIMEZONE)
+    }
+
+    /// Reminder lead times in minutes, largest first
+    pub fn reminders(&self) -> Vec<i64> {
+        self.reminders
+            .split(',')
+            .filter_map(|s| s.trim().parse().ok())
+            .sorted()
+            .rev()
+            .collect()
+    }
+
+    /// Quiet window as minutes of day in user's timezone, end is exclusive
+    pub fn quiet(&self) -> Option<(i64, i64)> {
+        self.quiet_start.zip(self.quiet_end)
+    }
+
+    /// Minutes before nagging about empty mission slot
+    pub fn idle_threshold(&self) -> Option<i64> {
+        self.idle_threshold
+    }
+
+    /// Preferred spaceship and duration type for fuel alert
+    pub fn fuel_alert(&self) -> Option<(Spaceship, DurationType)> {
+        let (ship, duration) = self.fuel_alert.as_ref()?.split_once(':')?;
+        Some((
+            Spaceship::try_from(ship.parse::<i32>().ok()?).ok()?,
+            DurationType::try_from(duration.parse::<i32>().ok()?).ok()?,
+        ))
+    }
+
+    /// Notify when piggy bank is full
+    pub fn piggy_alert(&self) -> bool {
+        self.piggy_alert
+    }
+
+    /// Notify when daily gift is not collected
+    pub fn gift_alert(&self) -> bool {
+        self.gift_alert
+    }
+
+    /// Broadcast new contracts and events
+    pub fn announce(&self) -> bool {
+        self.announce
+    }
+
+    pub fn is_quiet(&self, timestamp: i64) -> bool {
+        let Some((start, end)) = self.quiet() else {
+            return false;
+        };
+        let Some(time) = DateTime::from_timestamp(timestamp, 0) else {
+            return false;
+        };
+        let local = time.with_timezone(&self.timezone());
+        let minute = (local.hour() * 60 + local.minute()) as i64;
+        if start <= end {
+            start <= minute && minute < end
+        } else {
+            minute >= start || minute < end
+        }
+    }
+}
+
+#[derive(Clone, Debug, FromRow)]
+pub struct PendingMessage {
+    id: i64,
+    #[allow(unused)]
+    user: i64,
+    text: String,
+    #[allow(unused)]
+    timestamp: i64,
+}
+
+impl PendingMessage {
+    pub fn id(&self) -> i64 {
+        self.id
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+#[derive(Clone, Debug, FromRow)]
+pub struct AccountGrowth {
+    ei: String,
+    day: i64,
+    soul_eggs: f64,
+    prophecy_eggs: i64,
+    earning_bonus: f64,
+}
+
+impl AccountGrowth {
+    pub fn new(
+        ei: String,
+        timestamp: i64,
+        soul_eggs: f64,
+        prophecy_eggs: i64,
+        earning_bonus: f64,
+    ) -> Self {
+        Self {
+            ei,
+            day: timestamp / 86400,
+            soul_eggs,
+            prophecy_eggs,
+            earning_bonus,
+        }
+    }
+
+    pub fn ei(&self) -> &str {
+        &self.ei
+    }
+
+    /// Days since unix epoch (UTC)
+    pub fn day(&self) -> i64 {
+        self.day
+    }
+
+    pub fn soul_eggs(&self) -> f64 {
+        self.soul_eggs
+    }
+
+    pub fn prophecy_eggs(&self) -> i64 {
+        self.prophecy_eggs
+    }
+
+    /// Earning bonus in percent
+    pub fn earning_bonus(&self) -> f64 {
+        self.earning_bonus
+    }
+}
+
+#[derive(Clone, Debug, FromRow)]
+pub struct ContractHistory {
+    ei: String,
+    id: String,
+    room: String,
+    grade: i64,
+    cxp: f64,
+    cxp_change: f64,
+    grade_performance: i64,
+    contribution_ratio: f64,
+    completion_percent: f64,
+    issues: String,
+    timestamp: f64,
+}
+
+impl ContractHistory {
+    pub fn from_evaluation(ei: String, evaluation: &ContractEvaluation) -> Self {
+        Self {
+            ei,
+            id: evaluation.contract_identifier().to_string(),
+            room: evaluation.coop_identifier().to_string(),
+            grade: evaluation.grade() as i64,
+            cxp: evaluation.cxp(),
+            cxp_change: evaluation.cxp_change(),
+            grade_performance: evaluation.grade_performance() as i64,
+            contribution_ratio: evaluation.contribution_ratio(),
+            completion_percent: evaluation.completion_percent(),
+            issues: evaluation
+                .issues()
+                .filter(|issue| *issue != PoorBehavior::None)
+                .map(|issue| issue.as_str_name())
+                .join(","),
+            timestamp: evaluation.evaluation_start_time(),
+        }
+    }
+
+    pub fn ei(&self) -> &str {
+        &self.ei
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn room(&self) -> &str {
+        &self.room
+    }
+
+    pub fn grade(&self) -> PlayerGrade {
+        PlayerGrade::try_from(self.grade as i32).unwrap_or_default()
+    }
+
+    /// Contract score
+    pub fn cxp(&self) -> f64 {
+        self.cxp
+    }
+
+    pub fn cxp_change(&self) -> f64 {
+        self.cxp_change
+    }
+
+    pub fn grade_performance(&self) -> i64 {
+        self.grade_performance
+    }
+
+    pub fn contribution_ratio(&self) -> f64 {
+        self.contribution_ratio
+    }
+
+    pub fn completion_percent(&self) -> f64 {
+        self.completion_percent
+    }
+
+    pub fn issues(&self) -> Vec<&str> {
+        self.issues.split(',').filter(|s| !s.is_empty()).collect()
+    }
+
+    pub fn timestamp(&self) -> f64 {
+        self.timestamp
+    }
+}
+
+#[derive(Clone, Debug, FromRow)]
+pub struct ContractSeason {
+    id: String,
+    name: String,
+    start_time: f64,
+    info: Vec<u8>,
+    contracts: String,
+    timestamp: i64,
+}
+
+impl ContractSeason {
+    pub fn new(info: &ContractSeasonInfo, contracts: Vec<String>) -> Self {
+        Self {
+            id: info.id().to_string(),
+            name: info.name().to_string(),
+            start_time: info.start_time(),
+            info: encode_to_byte(info),
+            contracts: contracts.join(","),
+            timestamp: kstool::time::get_current_second() as i64,
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn start_time(&self) -> f64 {
+        self.start_time
+    }
+
+    pub fn raw_info(&self) -> &[u8] {
+        &self.info
+    }
+
+    pub fn info(&self) -> Option<ContractSeasonInfo> {
+        <ContractSeasonInfo as prost::Message>::decode(self.info.as_slice())
+            .inspect_err(|e| log::warn!("Decode season {} error: {e:?}", self.id))
+            .ok()
+    }
+
+    /// Season contracts currently offered by server
+    pub fn contracts(&self) -> Vec<&str> {
+        self.contracts
+            .split(',')
+            .filter(|s| !s.is_empty())
+            .collect()
+    }
+
+    pub fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn quiet_setting(start: i64, end: i64) -> UserSetting {
+        let mut setting = UserSetting::new(0);
+        setting.timezone = Some("Asia/Tokyo".into());
+        setting.quiet_start = Some(start);
+        setting.quiet_end = Some(end);
+        setting
+    }
+
+    // 2025-01-01 00:00 in Asia/Tokyo (UTC+9) plus minutes
+    fn tokyo(minute: i64) -> i64 {
+        1735657200 + minute * 60
+    }
+
+    #[test]
+    fn test_quiet_same_day() {
+        let setting = quiet_setting(9 * 60, 17 * 60);
+        assert!(!setting.is_quiet(tokyo(9 * 60 - 1)));
+        assert!(setting.is_quiet(tokyo(9 * 60)));
+        assert!(setting.is_quiet(tokyo(17 * 60 - 1)));
+        assert!(!setting.is_quiet(tokyo(17 * 60)));
+    }
+
+    #[test]
+    fn test_quiet_past_midnight() {
+        let setting = quiet_setting(23 * 60, 7 * 60);
+        assert!(!setting.is_quiet(tokyo(22 * 60 + 59)));
+        assert!(setting.is_quiet(tokyo(23 * 60)));
+        assert!(setting.is_quiet(tokyo(0)));
+        assert!(setting.is_quiet(tokyo(7 * 60 - 1)));
+        assert!(!setting.is_quiet(tokyo(7 * 60)));
+        assert!(!setting.is_quiet(tokyo(12 * 60)));
+    }
+
+    #[test]
+    fn test_quiet_empty_window() {
+        let setting = quiet_setting(8 * 60, 8 * 60);
+        assert!(!setting.is_quiet(tokyo(8 * 60)));
+        assert!(!setting.is_quiet(tokyo(20 * 60)));
+        assert!(!UserSetting::new(0).is_quiet(tokyo(0)));
+    }
+}
@@ -1,13 +1,33 @@
 pub mod v1;
+pub mod v10;
+pub mod v11;
+pub mod v12;
+pub mod v13;
+pub mod v14;
+pub mod v15;
+pub mod v16;
+pub mod v17;
+pub mod v18;
+pub mod v19;
 pub mod v2;
+pub mod v20;
+pub mod v21;
+pub mod v22;
+pub mod v23;
+pub mod v24;
+pub mod v25;
 pub mod v3;
 pub mod v4;
 pub mod v5;
 pub mod v6;
 pub mod v7;
 pub mod v8;
+pub mod v9;
 
 pub mod prelude {
-    pub use super::v8 as current;
-    pub use super::{v1, v2, v3, v4, v5, v6, v7, v8};
+    pub use super::v25 as current;
+    pub use super::{
+        v1, v2, v3, v4, v5, v6, v7, v8, v9, v10, v11, v12, v13, v14, v15, v16, v17, v18, v19, v20,
+        v21, v22, v23, v24, v25,
+    };
 }
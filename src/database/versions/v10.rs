@@ -0,0 +1,22 @@
+use log::info;
+use sqlx::SqliteConnection;
+
+pub const VERSION: &str = "10";
+
+const MERGE_STATEMENT_STAGE: &str = r#"
+        ALTER TABLE "spaceship" ADD COLUMN "reminded" INTEGER;
+
+        ALTER TABLE "user_setting" ADD COLUMN "reminders" TEXT NOT NULL DEFAULT '';
+
+        UPDATE "meta" SET "value" = '10' WHERE "key" = 'version';
+    "#;
+
+pub async fn merge_v9(conn: &mut SqliteConnection) -> sqlx::Result<()> {
+    info!("Performing database prepare stage (v10)");
+    sqlx::raw_sql(MERGE_STATEMENT_STAGE)
+        .execute(&mut *conn)
+        .await?;
+
+    info!("Merge completed");
+    Ok(())
+}
@@ -0,0 +1,28 @@
+use log::info;
+use sqlx::SqliteConnection;
+
+pub const VERSION: &str = "11";
+
+const MERGE_STATEMENT_STAGE: &str = r#"
+        ALTER TABLE "user_setting" ADD COLUMN "quiet_start" INTEGER;
+
+        ALTER TABLE "user_setting" ADD COLUMN "quiet_end" INTEGER;
+
+        CREATE TABLE "pending_message" (
+            "user"      INTEGER NOT NULL,
+            "text"      TEXT NOT NULL,
+            "timestamp" INTEGER NOT NULL
+        );
+
+        UPDATE "meta" SET "value" = '11' WHERE "key" = 'version';
+    "#;
+
+pub async fn merge_v10(conn: &mut SqliteConnection) -> sqlx::Result<()> {
+    info!("Performing database prepare stage (v11)");
+    sqlx::raw_sql(MERGE_STATEMENT_STAGE)
+        .execute(&mut *conn)
+        .await?;
+
+    info!("Merge completed");
+    Ok(())
+}
@@ -0,0 +1,22 @@
+use log::info;
+use sqlx::SqliteConnection;
+
+pub const VERSION: &str = "12";
+
+const MERGE_STATEMENT_STAGE: &str = r#"
+        ALTER TABLE "account" ADD COLUMN "idle_since" INTEGER;
+
+        ALTER TABLE "user_setting" ADD COLUMN "idle_threshold" INTEGER;
+
+        UPDATE "meta" SET "value" = '12' WHERE "key" = 'version';
+    "#;
+
+pub async fn merge_v11(conn: &mut SqliteConnection) -> sqlx::Result<()> {
+    info!("Performing database prepare stage (v12)");
+    sqlx::raw_sql(MERGE_STATEMENT_STAGE)
+        .execute(&mut *conn)
+        .await?;
+
+    info!("Merge completed");
+    Ok(())
+}
@@ -0,0 +1,28 @@
+use log::info;
+use sqlx::SqliteConnection;
+
+pub const VERSION: &str = "13";
+
+const MERGE_STATEMENT_STAGE: &str = r#"
+        ALTER TABLE "spaceship" ADD COLUMN "level" INTEGER NOT NULL DEFAULT 0;
+
+        ALTER TABLE "spaceship" ADD COLUMN "capacity" INTEGER NOT NULL DEFAULT 0;
+
+        ALTER TABLE "spaceship" ADD COLUMN "target" INTEGER;
+
+        ALTER TABLE "spaceship" ADD COLUMN "status" INTEGER NOT NULL DEFAULT 0;
+
+        ALTER TABLE "spaceship" ADD COLUMN "mission_type" INTEGER NOT NULL DEFAULT 0;
+
+        UPDATE "meta" SET "value" = '13' WHERE "key" = 'version';
+    "#;
+
+pub async fn merge_v12(conn: &mut SqliteConnection) -> sqlx::Result<()> {
+    info!("Performing database prepare stage (v13)");
+    sqlx::raw_sql(MERGE_STATEMENT_STAGE)
+        .execute(&mut *conn)
+        .await?;
+
+    info!("Merge completed");
+    Ok(())
+}
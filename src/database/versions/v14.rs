@@ -0,0 +1,26 @@
+use log::info;
+use sqlx::SqliteConnection;
+
+pub const VERSION: &str = "14";
+
+const MERGE_STATEMENT_STAGE: &str = r#"
+        ALTER TABLE "user_setting" ADD COLUMN "fuel_alert" TEXT;
+
+        CREATE TABLE "fuel_notified" (
+            "user"      INTEGER NOT NULL,
+            "ei"        TEXT NOT NULL,
+            PRIMARY KEY("user", "ei")
+        );
+
+        UPDATE "meta" SET "value" = '14' WHERE "key" = 'version';
+    "#;
+
+pub async fn merge_v13(conn: &mut SqliteConnection) -> sqlx::Result<()> {
+    info!("Performing database prepare stage (v14)");
+    sqlx::raw_sql(MERGE_STATEMENT_STAGE)
+        .execute(&mut *conn)
+        .await?;
+
+    info!("Merge completed");
+    Ok(())
+}
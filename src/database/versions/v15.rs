@@ -0,0 +1,27 @@
+use log::info;
+use sqlx::SqliteConnection;
+
+pub const VERSION: &str = "15";
+
+const MERGE_STATEMENT_STAGE: &str = r#"
+        CREATE TABLE "account_growth" (
+            "ei"        TEXT NOT NULL,
+            "day"       INTEGER NOT NULL,
+            "soul_eggs" REAL NOT NULL,
+            "prophecy_eggs" INTEGER NOT NULL,
+            "earning_bonus" REAL NOT NULL,
+            PRIMARY KEY("ei", "day")
+        );
+
+        UPDATE "meta" SET "value" = '15' WHERE "key" = 'version';
+    "#;
+
+pub async fn merge_v14(conn: &mut SqliteConnection) -> sqlx::Result<()> {
+    info!("Performing database prepare stage (v15)");
+    sqlx::raw_sql(MERGE_STATEMENT_STAGE)
+        .execute(&mut *conn)
+        .await?;
+
+    info!("Merge completed");
+    Ok(())
+}
@@ -0,0 +1,20 @@
+use log::info;
+use sqlx::SqliteConnection;
+
+pub const VERSION: &str = "16";
+
+const MERGE_STATEMENT_STAGE: &str = r#"
+        ALTER TABLE "account" ADD COLUMN "backup_time" INTEGER;
+
+        UPDATE "meta" SET "value" = '16' WHERE "key" = 'version';
+    "#;
+
+pub async fn merge_v15(conn: &mut SqliteConnection) -> sqlx::Result<()> {
+    info!("Performing database prepare stage (v16)");
+    sqlx::raw_sql(MERGE_STATEMENT_STAGE)
+        .execute(&mut *conn)
+        .await?;
+
+    info!("Merge completed");
+    Ok(())
+}
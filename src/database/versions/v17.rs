@@ -0,0 +1,28 @@
+use log::info;
+use sqlx::SqliteConnection;
+
+pub const VERSION: &str = "17";
+
+const MERGE_STATEMENT_STAGE: &str = r#"
+        ALTER TABLE "user_setting" ADD COLUMN "piggy_alert" INTEGER NOT NULL DEFAULT 0;
+        ALTER TABLE "user_setting" ADD COLUMN "gift_alert" INTEGER NOT NULL DEFAULT 0;
+
+        CREATE TABLE "account_notification" (
+            "ei"        TEXT NOT NULL,
+            "kind"      TEXT NOT NULL,
+            "key"       TEXT NOT NULL,
+            PRIMARY KEY("ei", "kind")
+        );
+
+        UPDATE "meta" SET "value" = '17' WHERE "key" = 'version';
+    "#;
+
+pub async fn merge_v16(conn: &mut SqliteConnection) -> sqlx::Result<()> {
+    info!("Performing database prepare stage (v17)");
+    sqlx::raw_sql(MERGE_STATEMENT_STAGE)
+        .execute(&mut *conn)
+        .await?;
+
+    info!("Merge completed");
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use log::info;
+use sqlx::SqliteConnection;
+
+pub const VERSION: &str = "18";
+
+const MERGE_STATEMENT_STAGE: &str = r#"
+        CREATE TABLE "contract_history" (
+            "ei"        TEXT NOT NULL,
+            "id"        TEXT NOT NULL,
+            "room"      TEXT NOT NULL,
+            "grade"     INTEGER NOT NULL,
+            "cxp"       REAL NOT NULL,
+            "cxp_change" REAL NOT NULL,
+            "grade_performance" INTEGER NOT NULL,
+            "contribution_ratio" REAL NOT NULL,
+            "completion_percent" REAL NOT NULL,
+            "issues"    TEXT NOT NULL DEFAULT '',
+            "timestamp" REAL NOT NULL,
+            PRIMARY KEY("ei", "id", "room")
+        );
+
+        UPDATE "meta" SET "value" = '18' WHERE "key" = 'version';
+    "#;
+
+pub async fn merge_v17(conn: &mut SqliteConnection) -> sqlx::Result<()> {
+    info!("Performing database prepare stage (v18)");
+    sqlx::raw_sql(MERGE_STATEMENT_STAGE)
+        .execute(&mut *conn)
+        .await?;
+
+    info!("Merge completed");
+    Ok(())
+}
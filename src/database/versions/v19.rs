@@ -0,0 +1,28 @@
+use log::info;
+use sqlx::SqliteConnection;
+
+pub const VERSION: &str = "19";
+
+const MERGE_STATEMENT_STAGE: &str = r#"
+        CREATE TABLE "contract_season" (
+            "id"        TEXT NOT NULL,
+            "name"      TEXT NOT NULL,
+            "start_time" REAL NOT NULL,
+            "info"      BLOB NOT NULL,
+            "contracts" TEXT NOT NULL DEFAULT '',
+            "timestamp" INTEGER NOT NULL,
+            PRIMARY KEY("id")
+        );
+
+        UPDATE "meta" SET "value" = '19' WHERE "key" = 'version';
+    "#;
+
+pub async fn merge_v18(conn: &mut SqliteConnection) -> sqlx::Result<()> {
+    info!("Performing database prepare stage (v19)");
+    sqlx::raw_sql(MERGE_STATEMENT_STAGE)
+        .execute(&mut *conn)
+        .await?;
+
+    info!("Merge completed");
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use log::info;
+use sqlx::SqliteConnection;
+
+pub const VERSION: &str = "20";
+
+const MERGE_STATEMENT_STAGE: &str = r#"
+        ALTER TABLE "user_setting" ADD COLUMN "announce" INTEGER NOT NULL DEFAULT 0;
+
+        CREATE TABLE "periodical_seen" (
+            "kind"      TEXT NOT NULL,
+            "id"        TEXT NOT NULL,
+            "timestamp" INTEGER NOT NULL,
+            PRIMARY KEY("kind", "id")
+        );
+
+        UPDATE "meta" SET "value" = '20' WHERE "key" = 'version';
+    "#;
+
+pub async fn merge_v19(conn: &mut SqliteConnection) -> sqlx::Result<()> {
+    info!("Performing database prepare stage (v20)");
+    sqlx::raw_sql(MERGE_STATEMENT_STAGE)
+        .execute(&mut *conn)
+        .await?;
+
+    info!("Merge completed");
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use log::info;
+use sqlx::SqliteConnection;
+
+pub const VERSION: &str = "21";
+
+const MERGE_STATEMENT_STAGE: &str = r#"
+        CREATE TABLE "contract_1" (
+            "id"	TEXT NOT NULL,
+            "size"  INTEGER NOT NULL,
+            "token_time" REAL NOT NULL,
+            "body"	BLOB NOT NULL,
+            "start_time" REAL NOT NULL DEFAULT 0,
+            PRIMARY KEY("id", "start_time")
+        );
+
+        INSERT INTO "contract_1" ("id", "size", "token_time", "body")
+            SELECT "id", "size", "token_time", "body" FROM "contract";
+
+        DROP TABLE "contract";
+
+        ALTER TABLE "contract_1" RENAME TO "contract";
+
+        UPDATE "meta" SET "value" = '21' WHERE "key" = 'version';
+    "#;
+
+pub async fn merge_v20(conn: &mut SqliteConnection) -> sqlx::Result<()> {
+    info!("Performing database prepare stage (v21)");
+    sqlx::raw_sql(MERGE_STATEMENT_STAGE)
+        .execute(&mut *conn)
+        .await?;
+
+    info!("Merge completed");
+    Ok(())
+}
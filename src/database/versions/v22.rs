@@ -0,0 +1,21 @@
+use log::info;
+use sqlx::SqliteConnection;
+
+pub const VERSION: &str = "22";
+
+const MERGE_STATEMENT_STAGE: &str = r#"
+        ALTER TABLE "contract" ADD COLUMN "egg" INTEGER NOT NULL DEFAULT 0;
+        ALTER TABLE "contract" ADD COLUMN "custom_egg" TEXT;
+
+        UPDATE "meta" SET "value" = '22' WHERE "key" = 'version';
+    "#;
+
+pub async fn merge_v21(conn: &mut SqliteConnection) -> sqlx::Result<()> {
+    info!("Performing database prepare stage (v22)");
+    sqlx::raw_sql(MERGE_STATEMENT_STAGE)
+        .execute(&mut *conn)
+        .await?;
+
+    info!("Merge completed");
+    Ok(())
+}
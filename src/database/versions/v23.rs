@@ -0,0 +1,21 @@
+use log::info;
+use sqlx::SqliteConnection;
+
+pub const VERSION: &str = "23";
+
+const MERGE_STATEMENT_STAGE: &str = r#"
+        ALTER TABLE "contract" ADD COLUMN "name" TEXT NOT NULL DEFAULT '';
+        ALTER TABLE "contract" ADD COLUMN "expiration_time" REAL NOT NULL DEFAULT 0;
+
+        UPDATE "meta" SET "value" = '23' WHERE "key" = 'version';
+    "#;
+
+pub async fn merge_v22(conn: &mut SqliteConnection) -> sqlx::Result<()> {
+    info!("Performing database prepare stage (v23)");
+    sqlx::raw_sql(MERGE_STATEMENT_STAGE)
+        .execute(&mut *conn)
+        .await?;
+
+    info!("Merge completed");
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use log::info;
+use sqlx::SqliteConnection;
+
+pub const VERSION: &str = "24";
+
+const MERGE_STATEMENT_STAGE: &str = r#"
+        CREATE TABLE "account_notification_1" (
+            "ei"        TEXT NOT NULL,
+            "kind"      TEXT NOT NULL,
+            "key"       TEXT NOT NULL,
+            "timestamp" INTEGER NOT NULL,
+            PRIMARY KEY("ei", "kind", "key")
+        );
+
+        INSERT INTO "account_notification_1" ("ei", "kind", "key", "timestamp")
+            SELECT "ei", "kind", "key", CAST(strftime('%s', 'now') AS INTEGER) FROM "account_notification";
+
+        DROP TABLE "account_notification";
+
+        ALTER TABLE "account_notification_1" RENAME TO "account_notification";
+
+        UPDATE "meta" SET "value" = '24' WHERE "key" = 'version';
+    "#;
+
+pub async fn merge_v23(conn: &mut SqliteConnection) -> sqlx::Result<()> {
+    info!("Performing database prepare stage (v24)");
+    sqlx::raw_sql(MERGE_STATEMENT_STAGE)
+        .execute(&mut *conn)
+        .await?;
+
+    info!("Merge completed");
+    Ok(())
+}
@@ -0,0 +1,211 @@
+use log::info;
+use sqlx::SqliteConnection;
+
+pub const VERSION: &str = "25";
+pub const CREATE_STATEMENT: &str = r#"
+        CREATE TABLE "account" (
+            "ei"        TEXT NOT NULL,
+            "nickname"  TEXT,
+            "last_fetch"    INTEGER NOT NULL DEFAULT 0,
+            "contract_trace" INTEGER NOT NULL DEFAULT 0,
+            "disabled"  INTEGER NOT NULL DEFAULT 0,
+            "idle_since" INTEGER,
+            "backup_time" INTEGER,
+            PRIMARY KEY("ei")
+        );
+
+        CREATE TABLE "user" (
+            "id" INTEGER NOT NULL,
+            "accounts" TEXT NOT NULL,
+            PRIMARY KEY("id")
+        );
+
+        CREATE TABLE "account_map" (
+            "ei" TEXT NOT NULL,
+            "users" TEXT NOT NULL,
+            PRIMARY KEY("ei")
+        );
+
+        CREATE TABLE "meta" (
+            "key"       TEXT NOT NULL,
+            "value"     TEXT,
+            PRIMARY KEY("key")
+        );
+
+        CREATE TABLE "spaceship" (
+            "id"        TEXT NOT NULL,
+            "name"      TEXT NOT NULL,
+            "duration_type"  INTEGER NOT NULL,
+            "belong"    TEXT NOT NULL,
+            "land"      INTEGER NOT NULL,
+            "notified" INTEGER NOT NULL DEFAULT 0,
+            "reminded" INTEGER,
+            "level"     INTEGER NOT NULL DEFAULT 0,
+            "capacity"  INTEGER NOT NULL DEFAULT 0,
+            "target"    INTEGER,
+            "status"    INTEGER NOT NULL DEFAULT 0,
+            "mission_type" INTEGER NOT NULL DEFAULT 0,
+            PRIMARY KEY("id")
+        );
+
+        CREATE TABLE "player_contract" (
+            "id"	TEXT NOT NULL,
+            "room"	TEXT NOT NULL,
+            "belong"	TEXT NOT NULL,
+            "start_time" REAL,
+            "finished"	INTEGER NOT NULL,
+            PRIMARY KEY("id", "belong")
+        );
+
+        CREATE TABLE "contract" (
+            "id"	TEXT NOT NULL,
+            "size"  INTEGER NOT NULL,
+            "token_time" REAL NOT NULL,
+            "body"	BLOB NOT NULL,
+            "start_time" REAL NOT NULL DEFAULT 0,
+            "egg" INTEGER NOT NULL DEFAULT 0,
+            "custom_egg" TEXT,
+            "name" TEXT NOT NULL DEFAULT '',
+            "expiration_time" REAL NOT NULL DEFAULT 0,
+            PRIMARY KEY("id", "start_time")
+        );
+
+        CREATE TABLE "contract_cache" (
+            "id"	TEXT NOT NULL,
+            "room"	TEXT NOT NULL,
+            "body"	BLOB NOT NULL,
+            "timestamp"	INTEGER NOT NULL,
+            "cleared"	INTEGER NOT NULL,
+            PRIMARY KEY("id", "room")
+        );
+
+        CREATE TABLE "subscriber" (
+            "contract"	TEXT NOT NULL,
+            "room"	TEXT NOT NULL,
+            "users"	TEXT NOT NULL,
+            "est"	INTEGER NOT NULL,
+	        "notified"	INTEGER NOT NULL DEFAULT 0,
+            PRIMARY KEY("contract", "room")
+        );
+
+        CREATE TABLE "account_cache" (
+            "ei"        TEXT NOT NULL,
+            "cache"     TEXT NOT NULL,
+            "timestamp" INTEGER NOT NULL,
+            PRIMARY KEY("ei")
+        );
+
+        CREATE TABLE "user_setting" (
+            "id"        INTEGER NOT NULL,
+            "timezone"  TEXT,
+            "reminders" TEXT NOT NULL DEFAULT '',
+            "quiet_start" INTEGER,
+            "quiet_end" INTEGER,
+            "idle_threshold" INTEGER,
+            "fuel_alert" TEXT,
+            "piggy_alert" INTEGER NOT NULL DEFAULT 0,
+            "gift_alert" INTEGER NOT NULL DEFAULT 0,
+            "announce"  INTEGER NOT NULL DEFAULT 0,
+            PRIMARY KEY("id")
+        );
+
+        CREATE TABLE "pending_message" (
+            "user"      INTEGER NOT NULL,
+            "text"      TEXT NOT NULL,
+            "timestamp" INTEGER NOT NULL
+        );
+
+        CREATE TABLE "fuel_notified" (
+            "user"      INTEGER NOT NULL,
+            "ei"        TEXT NOT NULL,
+            PRIMARY KEY("user", "ei")
+        );
+
+        CREATE TABLE "account_growth" (
+            "ei"        TEXT NOT NULL,
+            "day"       INTEGER NOT NULL,
+            "soul_eggs" REAL NOT NULL,
+            "prophecy_eggs" INTEGER NOT NULL,
+            "earning_bonus" REAL NOT NULL,
+            PRIMARY KEY("ei", "day")
+        );
+
+        CREATE TABLE "account_notification" (
+            "user"      INTEGER NOT NULL,
+            "ei"        TEXT NOT NULL,
+            "kind"      TEXT NOT NULL,
+            "key"       TEXT NOT NULL,
+            "timestamp" INTEGER NOT NULL,
+            PRIMARY KEY("user", "ei", "kind", "key")
+        );
+
+        CREATE TABLE "contract_history" (
+            "ei"        TEXT NOT NULL,
+            "id"        TEXT NOT NULL,
+            "room"      TEXT NOT NULL,
+            "grade"     INTEGER NOT NULL,
+            "cxp"       REAL NOT NULL,
+            "cxp_change" REAL NOT NULL,
+            "grade_performance" INTEGER NOT NULL,
+            "contribution_ratio" REAL NOT NULL,
+            "completion_percent" REAL NOT NULL,
+            "issues"    TEXT NOT NULL DEFAULT '',
+            "timestamp" REAL NOT NULL,
+            PRIMARY KEY("ei", "id", "room")
+        );
+
+        CREATE TABLE "contract_season" (
+            "id"        TEXT NOT NULL,
+            "name"      TEXT NOT NULL,
+            "start_time" REAL NOT NULL,
+            "info"      BLOB NOT NULL,
+            "contracts" TEXT NOT NULL DEFAULT '',
+            "timestamp" INTEGER NOT NULL,
+            PRIMARY KEY("id")
+        );
+
+        CREATE TABLE "periodical_seen" (
+            "kind"      TEXT NOT NULL,
+            "id"        TEXT NOT NULL,
+            "timestamp" INTEGER NOT NULL,
+            PRIMARY KEY("kind", "id")
+        );
+    "#;
+
+const MERGE_STATEMENT_STAGE: &str = r#"
+        CREATE TABLE "account_notification_1" (
+            "user"      INTEGER NOT NULL,
+            "ei"        TEXT NOT NULL,
+            "kind"      TEXT NOT NULL,
+            "key"       TEXT NOT NULL,
+            "timestamp" INTEGER NOT NULL,
+            PRIMARY KEY("user", "ei", "kind", "key")
+        );
+
+        WITH RECURSIVE "split"("ei", "user", "rest") AS (
+            SELECT "ei", '', "users" || ',' FROM "account_map"
+            UNION ALL
+            SELECT "ei", substr("rest", 1, instr("rest", ',') - 1), substr("rest", instr("rest", ',') + 1)
+                FROM "split" WHERE "rest" <> ''
+        )
+        INSERT INTO "account_notification_1" ("user", "ei", "kind", "key", "timestamp")
+            SELECT CAST("split"."user" AS INTEGER), "n"."ei", "n"."kind", "n"."key", "n"."timestamp"
+            FROM "account_notification" AS "n" JOIN "split" ON "split"."ei" = "n"."ei"
+            WHERE "split"."user" <> '';
+
+        DROP TABLE "account_notification";
+
+        ALTER TABLE "account_notification_1" RENAME TO "account_notification";
+
+        UPDATE "meta" SET "value" = '25' WHERE "key" = 'version';
+    "#;
+
+pub async fn merge_v24(conn: &mut SqliteConnection) -> sqlx::Result<()> {
+    info!("Performing database prepare stage (v25)");
+    sqlx::raw_sql(MERGE_STATEMENT_STAGE)
+        .execute(&mut *conn)
+        .await?;
+
+    info!("Merge completed");
+    Ok(())
+}
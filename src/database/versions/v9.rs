@@ -0,0 +1,24 @@
+use log::info;
+use sqlx::SqliteConnection;
+
+pub const VERSION: &str = "9";
+
+const MERGE_STATEMENT_STAGE: &str = r#"
+        CREATE TABLE "user_setting" (
+            "id"        INTEGER NOT NULL,
+            "timezone"  TEXT,
+            PRIMARY KEY("id")
+        );
+
+        UPDATE "meta" SET "value" = '9' WHERE "key" = 'version';
+    "#;
+
+pub async fn merge_v8(conn: &mut SqliteConnection) -> sqlx::Result<()> {
+    info!("Performing database prepare stage (v9)");
+    sqlx::raw_sql(MERGE_STATEMENT_STAGE)
+        .execute(&mut *conn)
+        .await?;
+
+    info!("Merge completed");
+    Ok(())
+}
@@ -6,8 +6,13 @@ pub mod monitor;
 pub mod proto;
 pub mod types;
 
-pub use coop::{decode_and_calc_score, decode_coop_status, query_coop_status};
+pub use coop::{
+    CoopResult, TokenTransfer, WhatIfChange, coop_start_time, decode_and_calc_score,
+    decode_and_simulate, decode_buff_timeline, decode_coop_status, query_coop_status,
+};
 pub(crate) use functions::{
-    encode_to_byte, extract_contracts, extract_epic_research, is_contract_cleared,
-    request as ei_request,
+    artifact_name_to_str, calc_earning_bonus, decode_backup, earning_bonus_to_soul_power,
+    egg_name_to_str, encode_to_byte, extract_contracts, extract_epic_research, grade_to_str,
+    is_contract_cleared, parse_duration_type, parse_num_with_unit, parse_spaceship,
+    query_periodicals, request as ei_request, reward_type_to_str, soul_power_to_role,
 };
@@ -7,7 +7,7 @@ use crate::{
     database::types::ContractSpec,
     egg::functions::{build_coop_status_request, parse_num_with_unit},
 };
-pub use types::CoopResult;
+pub use types::{BuffTimeline, CoopResult};
 
 use super::{
     definitions::{API_BACKEND, OOM_UNIT},
@@ -15,11 +15,9 @@ use super::{
     proto::{self},
 };
 
-#[allow(unused)]
 static NUM_STR_RE: LazyLock<regex::Regex> =
     LazyLock::new(|| regex::Regex::new(r"^(\d+(\.\d+)?)(\w{1,2}|A Lot)?$").unwrap());
 
-#[allow(unused)]
 fn parse_num_str(s: &str) -> Option<f64> {
     let cap = NUM_STR_RE.captures(s)?;
 
@@ -137,10 +135,34 @@ pub async fn query_coop_status(
     Ok(res)
 }
 
+/// Boost tokens moved by one player, only known from that player's own backup
+#[derive(Clone, Debug)]
+pub struct TokenTransfer {
+    user_name: String,
+    given: u32,
+    received: u32,
+}
+
+impl TokenTransfer {
+    pub fn from_backup(backup: &proto::Backup, contract_id: &str) -> Option<Self> {
+        let farm = backup
+            .farms
+            .iter()
+            .find(|farm| farm.contract_id() == contract_id)?;
+        Some(Self {
+            user_name: backup.user_name().to_string(),
+            given: farm.boost_tokens_given(),
+            received: farm.boost_tokens_received(),
+        })
+    }
+}
+
 pub fn decode_and_calc_score(
     spec: ContractSpec,
     data: &[u8],
     authorized: bool,
+    tokens: Option<TokenTransfer>,
+    timestamp: i64,
 ) -> anyhow::Result<CoopResult> {
     let res: proto::ContractCoopStatusResponse = decode_data(data, authorized)?;
     /* let mut output = vec![];
@@ -163,14 +185,157 @@ pub fn decode_and_calc_score(
     )); */
 
     //println!("{res:#?}");
-    calc_score(spec, res)
+    calc_score(spec, res, tokens.as_ref(), timestamp)
+}
+
+/// Unix time when the coop started, chooses the spec version of a rerun contract
+pub fn coop_start_time(
+    spec: &ContractSpec,
+    data: &proto::ContractCoopStatusResponse,
+    timestamp: i64,
+) -> Option<f64> {
+    let grade_spec = spec.get(&data.grade())?;
+    Some(timestamp as f64 - (grade_spec.length() - data.seconds_remaining()))
+}
+
+pub fn decode_buff_timeline(
+    data: &[u8],
+    authorized: bool,
+    timestamp: i64,
+) -> anyhow::Result<Vec<BuffTimeline>> {
+    let res: proto::ContractCoopStatusResponse = decode_data(data, authorized)?;
+    Ok(BuffTimeline::from_status(&res, timestamp))
 }
 
 pub fn calc_score(
     spec: ContractSpec,
     data: proto::ContractCoopStatusResponse,
+    tokens: Option<&TokenTransfer>,
+    timestamp: i64,
 ) -> anyhow::Result<CoopResult> {
-    CoopScore::calc(data, &spec).map_err(|e| anyhow!("{e}"))
+    CoopScore::calc(data, &spec, tokens, timestamp).map_err(|e| anyhow!("{e}"))
+}
+
+/// Hypothetical change to coop status
+#[derive(Clone, Debug)]
+pub enum WhatIfChange {
+    /// Add egg laying rate (per hour) to member
+    AddElr(String, f64),
+    /// Multiply egg laying rate of member
+    MulElr(String, f64),
+    /// Every member fills habs now
+    Boost,
+    /// Member stops contributing
+    Leave(String),
+}
+
+impl WhatIfChange {
+    /// Accept `elr +<rate> <member>`, `elr x<multiplier> <member>`, `boost` and `leave <member>`
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+        let (kind, rest) = input.split_once(' ').unwrap_or((input, ""));
+        match kind {
+            "boost" if rest.is_empty() => Some(Self::Boost),
+            "leave" if !rest.is_empty() => Some(Self::Leave(rest.into())),
+            "elr" => {
+                let (value, member) = rest.split_once(' ')?;
+                if let Some(value) = value.strip_prefix('+') {
+                    Some(Self::AddElr(member.into(), parse_num_str(value)?))
+                } else {
+                    Some(Self::MulElr(
+                        member.into(),
+                        value
+                            .strip_prefix('x')?
+                            .parse()
+                            .ok()
+                            .filter(|multiplier: &f64| {
+                                multiplier.is_finite() && *multiplier > 0.0
+                            })?,
+                    ))
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Accept comma separated changes, any invalid change rejects the whole list
+    pub fn parse_list(input: &str) -> Option<Vec<Self>> {
+        input.split(',').map(Self::parse).collect()
+    }
+
+    fn apply(&self, data: &mut proto::ContractCoopStatusResponse) -> anyhow::Result<()> {
+        let find_member = |data: &mut proto::ContractCoopStatusResponse, member: &str| {
+            data.contributors
+                .iter()
+                .position(|x| x.user_name().eq_ignore_ascii_case(member))
+                .ok_or_else(|| anyhow!("Member {member} not found"))
+        };
+        match self {
+            Self::AddElr(member, rate) | Self::MulElr(member, rate) => {
+                let index = find_member(data, member)?;
+                let params = data.contributors[index]
+                    .production_params
+                    .as_mut()
+                    .ok_or_else(|| anyhow!("Member {member} is private"))?;
+                let population = params.farm_population();
+                if let Self::AddElr(..) = self {
+                    if population <= 0.0 {
+                        return Err(anyhow!("Member {member} has no chickens"));
+                    }
+                    params.elr = Some(params.elr() + rate / 3600.0 / population);
+                } else {
+                    params.elr = Some(params.elr() * rate);
+                }
+            }
+            Self::Boost => {
+                for params in data
+                    .contributors
+                    .iter_mut()
+                    .filter_map(|x| x.production_params.as_mut())
+                {
+                    params.farm_population =
+                        Some(params.farm_capacity().max(params.farm_population()));
+                }
+            }
+            Self::Leave(member) => {
+                let index = find_member(data, member)?;
+                data.contributors.remove(index);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for WhatIfChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AddElr(member, rate) => {
+                write!(f, "{member} ELR +{}/h", parse_num_with_unit(*rate))
+            }
+            Self::MulElr(member, rate) => write!(f, "{member} ELR x{rate}"),
+            Self::Boost => write!(f, "Everyone boosts to full habs"),
+            Self::Leave(member) => write!(f, "{member} leaves"),
+        }
+    }
+}
+
+/// Return score of current status and status after changes
+pub fn decode_and_simulate(
+    spec: ContractSpec,
+    data: &[u8],
+    authorized: bool,
+    changes: &[WhatIfChange],
+    timestamp: i64,
+) -> anyhow::Result<(CoopResult, CoopResult)> {
+    let res: proto::ContractCoopStatusResponse = decode_data(data, authorized)?;
+    let mut modified = res.clone();
+    for change in changes {
+        change.apply(&mut modified)?;
+    }
+    Ok((
+        calc_score(spec.clone(), res, None, timestamp)?,
+        calc_score(spec, modified, None, timestamp)?,
+    ))
 }
 
 mod types {
@@ -181,15 +346,23 @@ mod types {
     use crate::{
         database::types::ContractSpec,
         egg::{
-            definitions::{DEFAULT_EARNING_BONUS_ROLE, EARNING_BONUS_ROLE},
-            functions::grade_to_big_g,
+            functions::{calc_silo_time, grade_to_big_g, soul_power_to_role},
             proto::contract::PlayerGrade,
             types::ContractGradeSpec,
         },
         types::fmt_time_delta_short,
     };
 
-    use super::parse_num_with_unit;
+    use super::{TokenTransfer, parse_num_with_unit};
+
+    // Unix time of a buff change, relative timestamps count back from when status was fetched
+    fn buff_time(server_timestamp: f64, timestamp: f64) -> f64 {
+        if server_timestamp > 0.0 {
+            server_timestamp
+        } else {
+            timestamp + server_timestamp
+        }
+    }
 
     fn calc_timestamp(timestamp: f64) -> f64 {
         if timestamp <= 0.0 {
@@ -244,10 +417,16 @@ mod types {
         egg_laying_rate: Option<f64>,
         finalized: bool,
         timestamp: Option<f64>,
+        silo_time: Option<f64>,
         soul_power: f64,
         permit_level: Option<u32>,
         coop_buff: (f64, f64),
+        boost_tokens: u32,
+        boost_tokens_spent: u32,
+        chicken_run_cooldown: f64,
         score: f64,
+        // (buff, chicken run, token) parts of teamwork bonus in percent
+        teamwork: (f64, f64, f64),
     }
 
     impl UserScore {
@@ -268,6 +447,11 @@ mod types {
         pub fn score(&self) -> f64 {
             self.score
         }
+
+        pub fn teamwork_score(&self) -> f64 {
+            let (b, r, t) = self.teamwork;
+            self.score * (1.0 + (b + r + t) / 100.0)
+        }
         pub fn sr(&self) -> String {
             if let Some(sr) = self.shipping_rate {
                 parse_num_with_unit(sr * 3600.0)
@@ -325,14 +509,32 @@ mod types {
 
                 if !cleared && !self.finalized {
                     if let Some(timestamp) = self.timestamp(cache_timestamp) {
+                        let offline = timestamp.abs();
                         write!(
                             fmt,
                             " _Offline_: {} \\({}\\)",
-                            fmt_time_delta_short(TimeDelta::seconds(timestamp.abs() as i64)),
-                            escape_func(&parse_num_with_unit(
-                                timestamp.abs() * self.egg_laying_rate.unwrap_or(0.0)
-                            ))
+                            fmt_time_delta_short(TimeDelta::seconds(offline as i64)),
+                            escape_func(&parse_num_with_unit(self.offline_eggs(offline)))
                         )?;
+                        if let Some(silo_time) = self.silo_time {
+                            if offline < silo_time {
+                                write!(
+                                    fmt,
+                                    " _Silo empty in_ {}",
+                                    fmt_time_delta_short(TimeDelta::seconds(
+                                        (silo_time - offline) as i64
+                                    ))
+                                )?;
+                            } else {
+                                write!(
+                                    fmt,
+                                    " _Silo empty_ {} ago",
+                                    fmt_time_delta_short(TimeDelta::seconds(
+                                        (offline - silo_time) as i64
+                                    ))
+                                )?;
+                            }
+                        }
                     } else {
                         write!(fmt, " \\[Private\\]")?;
                     }
@@ -343,21 +545,221 @@ mod types {
                     " \\(E:{:.0}%, L:{:.0}%\\)",
                     self.coop_buff.0, self.coop_buff.1
                 )?;
+
+                write!(
+                    fmt,
+                    " _Tokens:_ {} \\(spent {}\\)",
+                    self.boost_tokens, self.boost_tokens_spent
+                )?;
+
+                let cooldown = self.chicken_run_cooldown
+                    - cache_timestamp
+                        .map(|t| (kstool::time::get_current_second() as i64 - t) as f64)
+                        .unwrap_or_default();
+                if !cleared && cooldown > 0.0 {
+                    write!(
+                        fmt,
+                        " _CR cooldown:_ {}",
+                        fmt_time_delta_short(TimeDelta::seconds(cooldown as i64))
+                    )?;
+                }
+
+                let (b, r, t) = self.teamwork;
+                write!(
+                    fmt,
+                    " _Teamwork:_ B {} R {} T {}",
+                    escape_func(&format!("{b:.1}")),
+                    escape_func(&format!("{r:.1}")),
+                    escape_func(&format!("{t:.1}")),
+                )?;
             }
 
             write!(
                 fmt,
-                " _Score:_ __{}__ {}",
+                " _Score:_ __{}__ \\({}\\) {}",
                 self.score() as i64,
+                self.teamwork_score() as i64,
                 self.finalized()
             )?;
             Ok(fmt)
         }
 
         fn eb_role(&self) -> &str {
-            EARNING_BONUS_ROLE
-                .get(self.soul_power.floor() as usize)
-                .unwrap_or(&DEFAULT_EARNING_BONUS_ROLE)
+            soul_power_to_role(self.soul_power)
+        }
+
+        // Eggs shipped while offline, capped by shipping rate and silo time
+        fn offline_eggs(&self, offline: f64) -> f64 {
+            let rate = self
+                .egg_laying_rate
+                .unwrap_or(0.0)
+                .min(self.shipping_rate.unwrap_or(f64::MAX));
+            offline.min(self.silo_time.unwrap_or(f64::MAX)) * rate
+        }
+    }
+
+    #[derive(Clone)]
+    pub struct BuffTimeline {
+        username: String,
+        // (timestamp, earnings buff %, egg laying buff %)
+        changes: Vec<(f64, f64, f64)>,
+        average: (f64, f64),
+    }
+
+    impl BuffTimeline {
+        pub(super) fn from_status(
+            data: &super::proto::ContractCoopStatusResponse,
+            timestamp: i64,
+        ) -> Vec<Self> {
+            let timestamp = timestamp as f64;
+            let end = if data.all_goals_achieved() {
+                timestamp - data.seconds_since_all_goals_achieved()
+            } else {
+                timestamp
+            };
+
+            data.contributors
+                .iter()
+                .map(|player| {
+                    let changes = player
+                        .buff_history
+                        .iter()
+                        .map(|buff| {
+                            (
+                                buff_time(buff.server_timestamp(), timestamp),
+                                (buff.earnings() - 1.0) * 100.0,
+                                (buff.egg_laying_rate() - 1.0) * 100.0,
+                            )
+                        })
+                        .collect::<Vec<_>>();
+
+                    let (mut total, mut earnings, mut laying) = (0.0, 0.0, 0.0);
+                    for (index, (start, e, l)) in changes.iter().enumerate() {
+                        let next = changes.get(index + 1).map(|x| x.0).unwrap_or(end);
+                        let duration = (next.min(end) - start).max(0.0);
+                        total += duration;
+                        earnings += duration * e;
+                        laying += duration * l;
+                    }
+
+                    Self {
+                        username: player.user_name().into(),
+                        average: if total > 0.0 {
+                            (earnings / total, laying / total)
+                        } else {
+                            changes.last().map(|x| (x.1, x.2)).unwrap_or_default()
+                        },
+                        changes,
+                    }
+                })
+                .collect()
+        }
+
+        pub fn username(&self) -> &str {
+            &self.username
+        }
+
+        /// Number of skipped earlier changes and the latest `limit` changes
+        pub fn recent_changes(&self, limit: usize) -> (usize, &[(f64, f64, f64)]) {
+            let skipped = self.changes.len().saturating_sub(limit);
+            (skipped, &self.changes[skipped..])
+        }
+
+        pub fn average(&self) -> (f64, f64) {
+            self.average
+        }
+    }
+
+    /// Farm grows with internal hatchery until hab capacity or shipping rate is reached
+    #[derive(Clone, Copy)]
+    pub(super) struct FarmGrowth {
+        population: f64,
+        limit: f64,
+        elr: f64,
+        ihr: f64,
+        sr: f64,
+    }
+
+    impl FarmGrowth {
+        // Contract modifiers are already applied to production params
+        pub(super) fn new(params: &super::proto::FarmProductionParams) -> Self {
+            let population = params.farm_population();
+            let elr = params.elr();
+            let ihr = params.ihr();
+            let sr = params.sr();
+            let limit = if ihr > 0.0 && elr > 0.0 {
+                params.farm_capacity().min(sr / elr).max(population)
+            } else {
+                population
+            };
+            Self {
+                population,
+                limit,
+                elr,
+                ihr,
+                sr,
+            }
+        }
+
+        fn growth_time(&self) -> f64 {
+            if self.ihr > 0.0 {
+                (self.limit - self.population) / self.ihr
+            } else {
+                0.0
+            }
+        }
+
+        /// Egg laying rate (capped by shipping rate) after `time` seconds
+        pub(super) fn rate(&self, time: f64) -> f64 {
+            let population = (self.population + self.ihr * time).clamp(self.population, self.limit);
+            self.sr.min(self.elr * population)
+        }
+
+        /// Eggs shipped in next `time` seconds
+        pub(super) fn eggs(&self, time: f64) -> f64 {
+            let growth = self.growth_time().min(time);
+            self.elr * (self.population * growth + self.ihr * growth * growth / 2.0)
+                + self.rate(growth) * (time - growth)
+        }
+
+        /// Time to ship `remain` eggs by all farms
+        pub(super) fn solve(farms: &[Self], remain: f64) -> f64 {
+            let eggs = |time: f64| farms.iter().map(|farm| farm.eggs(time)).sum::<f64>();
+            let final_rate = farms
+                .iter()
+                .map(|farm| farm.rate(farm.growth_time()))
+                .sum::<f64>();
+            let final_rate = if final_rate == 0.0 { 0.001 } else { final_rate };
+
+            if remain <= 0.0 {
+                let current_rate = farms.iter().map(|farm| farm.rate(0.0)).sum::<f64>();
+                let current_rate = if current_rate == 0.0 {
+                    0.001
+                } else {
+                    current_rate
+                };
+                return remain / current_rate;
+            }
+
+            let growth_time = farms
+                .iter()
+                .map(|farm| farm.growth_time())
+                .fold(0.0, f64::max);
+            let grown = eggs(growth_time);
+            if grown < remain {
+                return growth_time + (remain - grown) / final_rate;
+            }
+
+            let (mut low, mut high) = (0.0, growth_time);
+            for _ in 0..64 {
+                let mid = (low + high) / 2.0;
+                if eggs(mid) < remain {
+                    low = mid;
+                } else {
+                    high = mid;
+                }
+            }
+            high
         }
     }
 
@@ -397,12 +799,17 @@ mod types {
         expect_remain_time: f64,
         contract_remain_time: f64,
         member: Vec<UserScore>,
+        // (username, amount)
+        chicken_runs: Vec<(String, u64)>,
+        gifts: Vec<(String, u32)>,
     }
 
     impl CoopScore {
         pub fn calc(
             data: super::proto::ContractCoopStatusResponse,
             spec: &ContractSpec,
+            tokens: Option<&TokenTransfer>,
+            timestamp: i64,
         ) -> Result<CoopResult, &'static str> {
             let Some(grade_spec) = spec.get(&data.grade()) else {
                 return Err("Grade spec not found");
@@ -418,7 +825,7 @@ mod types {
             )); */
 
             let (completion_time, expect_remain_time, _remain_time, players) =
-                match Self::calc_total_score(&data, grade_spec, spec) {
+                match Self::calc_total_score(&data, grade_spec, spec, tokens, timestamp) {
                     CoopStatus::Normal(c, e, r, user_scores) => (c, e, r, user_scores),
                     CoopStatus::OutOfTime(exp) => {
                         return Ok(CoopResult::OutOfTime(exp));
@@ -435,6 +842,16 @@ mod types {
                 current_amount: data.total_amount(),
                 contract_remain_time: data.seconds_remaining(),
                 member: players,
+                chicken_runs: data
+                    .chicken_runs
+                    .iter()
+                    .map(|run| (run.user_name().to_string(), run.amount()))
+                    .collect(),
+                gifts: data
+                    .gifts
+                    .iter()
+                    .map(|gift| (gift.user_name().to_string(), gift.amount()))
+                    .collect(),
             }))
         }
 
@@ -442,6 +859,8 @@ mod types {
             coop: &super::proto::ContractCoopStatusResponse,
             grade_spec: &ContractGradeSpec,
             spec: &ContractSpec,
+            tokens: Option<&TokenTransfer>,
+            timestamp: i64,
         ) -> CoopStatus {
             /* let pu = crate::egg::functions::parse_num_with_unit;
             let s2h = |value: f64| value * 3600.0; */
@@ -452,33 +871,31 @@ mod types {
 
             let (completion_time, expect_remain_time, remain_time) = if !coop.all_goals_achieved() {
                 let remain = grade_spec.goal3() - coop.total_amount();
-                let (total_elr, offline_egg) = coop
+                let (farms, offline_egg) = coop
                     .contributors
                     .iter()
                     .filter_map(|x| {
                         let farm_prams = x.production_params.as_ref()?;
                         let farm_info = x.farm_info.as_ref()?;
-                        let farm_elr = farm_prams
-                            .sr()
-                            .min(farm_prams.elr() * farm_prams.farm_population());
+                        let farm = FarmGrowth::new(farm_prams);
 
-                        // offline laying
-                        let player_offline_egg = calc_timestamp(farm_info.timestamp()) * farm_elr;
+                        // offline laying, stops when silos are empty
+                        let player_offline_egg = calc_timestamp(farm_info.timestamp())
+                            .min(calc_silo_time(farm_info))
+                            * farm.rate(0.0);
                         //log::trace!("Player {} egg {}", x.user_name(), pu(player_offline_egg));
-                        Some((farm_elr, player_offline_egg))
+                        Some((farm, player_offline_egg))
                     })
                     .fold(
-                        (0.0, 0.0),
-                        |(mut acc, mut offline_egg), (farm_elr, player_offline_egg)| {
-                            acc += farm_elr;
+                        (vec![], 0.0),
+                        |(mut farms, mut offline_egg), (farm, player_offline_egg)| {
+                            farms.push(farm);
                             offline_egg += player_offline_egg;
-                            //log::trace!("Player {} egg {}", x.user_name(), pu(player_offline_egg));
-                            (acc, offline_egg)
+                            (farms, offline_egg)
                         },
                     );
-                let total_elr = if total_elr == 0.0 { 0.001 } else { total_elr };
                 //log::trace!("{} {} {total_elr}", pu(remain), pu(offline_egg));
-                let expect_remain_time = (remain - offline_egg) / total_elr;
+                let expect_remain_time = FarmGrowth::solve(&farms, remain - offline_egg);
 
                 if expect_remain_time > coop_total_time {
                     return CoopStatus::OutOfTime(
@@ -526,6 +943,7 @@ mod types {
                     .unzip();
 
                 let user_timestamp = player.farm_info.as_ref().map(|x| x.timestamp());
+                let silo_time = player.farm_info.as_ref().map(calc_silo_time);
 
                 let score = Self::calc_score(
                     egg_laying_rate.and_then(|elr| Some(elr.min(shipping_rate?))),
@@ -535,8 +953,23 @@ mod types {
                     grade_spec.goal3().max(coop.total_amount()),
                     spec,
                     completion_time - expect_remain_time.min(0.0),
-                    expect_remain_time.max(0.0),
-                    calc_timestamp(user_timestamp.unwrap_or_default()),
+                    player
+                        .production_params
+                        .as_ref()
+                        .map(|params| FarmGrowth::new(params).eggs(expect_remain_time.max(0.0)))
+                        .unwrap_or_default(),
+                    calc_timestamp(user_timestamp.unwrap_or_default())
+                        .min(silo_time.unwrap_or(f64::MAX)),
+                );
+
+                let teamwork = Self::calc_teamwork(
+                    player,
+                    coop,
+                    grade_spec,
+                    spec,
+                    tokens.filter(|tokens| tokens.user_name == player.user_name()),
+                    completion_time - expect_remain_time.min(0.0),
+                    timestamp as f64,
                 );
 
                 players.push(UserScore {
@@ -546,6 +979,7 @@ mod types {
                     amount: player.contribution_amount(),
                     username: player.user_name().into(),
                     timestamp: user_timestamp,
+                    silo_time,
                     soul_power: player.soul_power(),
                     permit_level: player.farm_info.as_ref().map(|x| x.permit_level()),
                     coop_buff: player
@@ -558,7 +992,11 @@ mod types {
                             )
                         })
                         .unwrap_or_default(),
+                    boost_tokens: player.boost_tokens(),
+                    boost_tokens_spent: player.boost_tokens_spent(),
+                    chicken_run_cooldown: player.chicken_run_cooldown(),
                     score,
+                    teamwork,
                 });
                 /* print!(
                     "Player: {} completion time {completion_time}",
@@ -590,15 +1028,15 @@ mod types {
             total_delivered: f64,
             spec: &ContractSpec,
             completion_time: f64,
-            expect_remain_time: f64,
+            // Eggs expected to be laid before the contract completes
+            projected_eggs: f64,
             user_offline_time: f64,
         ) -> f64 {
             let coop_total_time = grade_spec.length();
             let coop_size = spec.max_coop_size() as f64;
-            let token_time = spec.token_time();
 
             let user_total_delivered =
-                contributions + total_elr.unwrap_or(0.0) * (expect_remain_time + user_offline_time);
+                contributions + projected_eggs + total_elr.unwrap_or(0.0) * user_offline_time;
             let ratio = (user_total_delivered * coop_size)
                 / grade_spec
                     .goal3()
@@ -610,21 +1048,84 @@ mod types {
                 } else {
                     3.0 * ratio.powf(0.15)
                 };
-            let t = 0.0075 * 0.8 * completion_time * 0.12 * 10.0;
-            let _big_b = 5.0 * 2.0f64.min(t / completion_time);
-
-            let big_a = completion_time / token_time;
-            let big_v = if big_a <= 42.0 { 3.0 } else { 0.07 * big_a };
-            let _big_t = 2.0 * (big_v.min(4.0) + 4.0 * big_v.min(2.0)) / big_v;
 
-            //let run_cap = 4.0;
-            let _big_r = 6.0f64.min(0.3f64.max(12.0 / coop_size / coop_total_time * 86400.0));
             187.5
                 * big_g
                 * big_c
                 * (1.0 + coop_total_time / 86400.0 / 3.0)
                 * (1.0 + 4.0 * (1.0 - completion_time / coop_total_time).powi(3))
-            //* (1.0 + (big_b + big_r + big_t) / 100.0)
+        }
+
+        // Returns the buff (B), chicken run (R) and token (T) parts of teamwork bonus in percent,
+        // the score multiplier is 1 + (B + R + T) / 100. Coop status only reports runs and gifts
+        // toward the requesting player, tokens are exact only with the player's own backup.
+        fn calc_teamwork(
+            player: &super::proto::contract_coop_status_response::ContributionInfo,
+            coop: &super::proto::ContractCoopStatusResponse,
+            grade_spec: &ContractGradeSpec,
+            spec: &ContractSpec,
+            tokens: Option<&TokenTransfer>,
+            completion_time: f64,
+            timestamp: f64,
+        ) -> (f64, f64, f64) {
+            let coop_total_time = grade_spec.length();
+            let coop_size = spec.max_coop_size() as f64;
+            let token_time = spec.token_time();
+            // Contract time elapsed when coop status was fetched
+            let elapsed = coop_total_time - coop.seconds_remaining();
+
+            // Buff time value, each buff lasts until the next change or contract completion
+            let mut btv = 0.0;
+            let mut buffs = player
+                .buff_history
+                .iter()
+                .map(|buff| {
+                    (
+                        (elapsed - (timestamp - buff_time(buff.server_timestamp(), timestamp)))
+                            .clamp(0.0, completion_time),
+                        buff,
+                    )
+                })
+                .peekable();
+            while let Some((start, buff)) = buffs.next() {
+                let end = buffs.peek().map(|(t, _)| *t).unwrap_or(completion_time);
+                btv += (end - start).max(0.0)
+                    * (0.075 * (buff.egg_laying_rate() - 1.0) * 100.0
+                        + 0.0075 * (buff.earnings() - 1.0) * 100.0);
+            }
+            let big_b = 5.0 * 2.0f64.min(btv / completion_time);
+
+            let runs = coop
+                .chicken_runs
+                .iter()
+                .filter(|run| run.user_id() == player.user_id())
+                .count() as f64;
+            let big_r = 6.0f64.min(runs * 0.3f64.max(12.0 / coop_size / coop_total_time * 86400.0));
+
+            let (given, received) = match tokens {
+                Some(tokens) => (tokens.given as f64, tokens.received as f64),
+                None => {
+                    // Tokens beyond the timer are received, missing ones are given away
+                    let gifted = coop
+                        .gifts
+                        .iter()
+                        .filter(|gift| gift.user_id() == player.user_id())
+                        .map(|gift| gift.amount() as f64)
+                        .sum::<f64>();
+                    let earned = (elapsed / token_time).floor();
+                    let obtained = (player.boost_tokens() + player.boost_tokens_spent()) as f64;
+                    (gifted.max(earned - obtained), (obtained - earned).max(0.0))
+                }
+            };
+            let big_a = completion_time / token_time;
+            let big_v = if big_a <= 42.0 { 3.0 } else { 0.07 * big_a };
+            let big_t = if given > 0.0 {
+                2.0 * (big_v.min(given) + 4.0 * big_v.min((given - received).max(0.0))) / big_v
+            } else {
+                0.0
+            };
+
+            (big_b, big_r, big_t)
         }
 
         pub fn grade_str(&self) -> &'static str {
@@ -646,6 +1147,10 @@ mod types {
             parse_num_with_unit(self.spec.goal3())
         }
 
+        pub fn modifiers(&self) -> Vec<String> {
+            self.spec.display_modifiers()
+        }
+
         fn get_timestamp_offset(original_timestamp: f64, cache_timestamp: Option<i64>) -> f64 {
             let current = kstool::time::get_current_second() as f64;
             if original_timestamp > 100000000.0 {
@@ -714,6 +1219,21 @@ mod types {
             let (e, l) = self.total_buff();
             format!("E: {e:.0}%, L: {l:.0}%")
         }
+
+        pub fn display_tokens(&self) -> String {
+            let (available, spent) = self.member.iter().fold((0, 0), |(available, spent), x| {
+                (available + x.boost_tokens, spent + x.boost_tokens_spent)
+            });
+            format!("{available} unspent, {spent} spent")
+        }
+
+        pub fn chicken_runs(&self) -> &[(String, u64)] {
+            &self.chicken_runs
+        }
+
+        pub fn gifts(&self) -> &[(String, u32)] {
+            &self.gifts
+        }
     }
 }
 
@@ -730,4 +1250,144 @@ mod test {
         assert_eq!(parse_num_str("0.00"), Some(0.0));
         assert_eq!(parse_num_str("3.5e16"), None);
     }
+
+    #[test]
+    fn test_parse_whatif() {
+        let changes =
+            WhatIfChange::parse_list("elr +1.5T Alice, elr x2 Bob,boost,leave Carol").unwrap();
+        assert_eq!(changes.len(), 4);
+        assert!(
+            matches!(&changes[0], WhatIfChange::AddElr(member, rate) if member == "Alice" && *rate == 1.5e12)
+        );
+        assert!(
+            matches!(&changes[1], WhatIfChange::MulElr(member, rate) if member == "Bob" && *rate == 2.0)
+        );
+        assert!(matches!(changes[2], WhatIfChange::Boost));
+        assert!(matches!(&changes[3], WhatIfChange::Leave(member) if member == "Carol"));
+
+        assert!(WhatIfChange::parse("").is_none());
+        assert!(WhatIfChange::parse_list("").is_none());
+        assert!(WhatIfChange::parse_list("boost,").is_none());
+
+        assert!(WhatIfChange::parse("elr x0 Bob").is_none());
+        assert!(WhatIfChange::parse("elr x-2 Bob").is_none());
+        assert!(WhatIfChange::parse("elr xNaN Bob").is_none());
+        assert!(WhatIfChange::parse("elr xinf Bob").is_none());
+        assert!(WhatIfChange::parse("elr xtwo Bob").is_none());
+    }
+
+    #[test]
+    fn test_whatif_add_elr_without_chickens() {
+        let mut data = proto::ContractCoopStatusResponse {
+            contributors: vec![proto::contract_coop_status_response::ContributionInfo {
+                user_name: Some("Alice".into()),
+                production_params: Some(proto::FarmProductionParams {
+                    farm_population: Some(0.0),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        assert!(
+            WhatIfChange::parse("elr +1T Alice")
+                .unwrap()
+                .apply(&mut data)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_buff_timeline() {
+        let buff =
+            |earnings: f64, egg_laying_rate: f64, server_timestamp: f64| proto::CoopBuffState {
+                egg_laying_rate: Some(egg_laying_rate),
+                earnings: Some(earnings),
+                server_timestamp: Some(server_timestamp),
+            };
+        let data = proto::ContractCoopStatusResponse {
+            contributors: vec![proto::contract_coop_status_response::ContributionInfo {
+                user_name: Some("Alice".into()),
+                // Hourly changes relative to fetch time, each buff lasts one hour
+                buff_history: vec![
+                    buff(1.0, 1.0, -5.0 * 3600.0),
+                    buff(1.1, 1.02, -4.0 * 3600.0),
+                    buff(1.2, 1.04, -3.0 * 3600.0),
+                    buff(1.1, 1.02, -2.0 * 3600.0),
+                    buff(1.3, 1.06, -3600.0),
+                ],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let timestamp = 1_700_000_000;
+        let timeline = BuffTimeline::from_status(&data, timestamp);
+        assert_eq!(timeline.len(), 1);
+        let (earnings, laying) = timeline[0].average();
+        assert_close(earnings, (10.0 + 20.0 + 10.0 + 30.0) / 5.0);
+        assert_close(laying, (2.0 + 4.0 + 2.0 + 6.0) / 5.0);
+
+        let (skipped, changes) = timeline[0].recent_changes(3);
+        assert_eq!(skipped, 2);
+        assert_eq!(changes.len(), 3);
+        assert_close(changes[0].0, timestamp as f64 - 3.0 * 3600.0);
+        assert_close(changes[2].1, 30.0);
+
+        let (skipped, changes) = timeline[0].recent_changes(10);
+        assert_eq!(skipped, 0);
+        assert_eq!(changes.len(), 5);
+        assert_eq!(timeline[0].recent_changes(0), (5, &[][..]));
+    }
+
+    fn farm(population: f64, capacity: f64, elr: f64, ihr: f64, sr: f64) -> types::FarmGrowth {
+        types::FarmGrowth::new(&proto::FarmProductionParams {
+            farm_population: Some(population),
+            farm_capacity: Some(capacity),
+            elr: Some(elr),
+            ihr: Some(ihr),
+            sr: Some(sr),
+            delivered: None,
+        })
+    }
+
+    fn assert_close(left: f64, right: f64) {
+        assert!((left - right).abs() < 1e-6, "{left} != {right}");
+    }
+
+    #[test]
+    fn test_farm_no_growth() {
+        let farm = farm(100.0, 200.0, 1.0, 0.0, 1000.0);
+        assert_close(farm.eggs(10.0), 1000.0);
+        assert_close(types::FarmGrowth::solve(&[farm], 500.0), 5.0);
+    }
+
+    #[test]
+    fn test_farm_growth_to_hab_capacity() {
+        let farm = farm(100.0, 200.0, 1.0, 10.0, 1e9);
+        assert_close(farm.rate(100.0), 200.0);
+        assert_close(farm.eggs(10.0), 1500.0);
+        assert_close(farm.eggs(20.0), 3500.0);
+        assert_close(types::FarmGrowth::solve(&[farm], 3500.0), 20.0);
+        assert_close(types::FarmGrowth::solve(&[farm], 1500.0), 10.0);
+        assert_close(types::FarmGrowth::solve(&[farm, farm], 3000.0), 10.0);
+    }
+
+    #[test]
+    fn test_farm_growth_capped_by_shipping() {
+        let farm = farm(100.0, 1000.0, 1.0, 10.0, 150.0);
+        assert_close(farm.rate(100.0), 150.0);
+        assert_close(farm.eggs(5.0), 625.0);
+        assert_close(farm.eggs(15.0), 2125.0);
+        assert_close(types::FarmGrowth::solve(&[farm], 2125.0), 15.0);
+    }
+
+    #[test]
+    fn test_farm_solve_remain_reached() {
+        let growing = farm(100.0, 200.0, 1.0, 10.0, 1e9);
+        assert_close(types::FarmGrowth::solve(&[growing], 0.0), 0.0);
+        assert_close(types::FarmGrowth::solve(&[growing], -200.0), -2.0);
+        // Idle farms use a tiny rate instead of dividing by zero
+        let idle = farm(0.0, 0.0, 0.0, 0.0, 0.0);
+        assert_close(types::FarmGrowth::solve(&[idle], -1.0), -1000.0);
+    }
 }
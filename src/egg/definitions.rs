@@ -109,3 +109,6 @@ pub(super) const EPIC_RESEARCH_NAME: &[&str] = &[
     "", //"warp_shift",        // should empty
     "", //"epic_silo_quality", // should empty
 ];
+
+// Fuel tank capacity by tank level
+pub(super) const FUEL_TANK_CAPACITY: &[f64] = &[2e9, 2e11, 1e13, 1e14, 2e14, 3e14, 4e14, 5e14];
@@ -122,6 +122,20 @@ pub fn build_first_contract_request(ei: String, device_id: Option<&str>) -> Stri
     encode_to_base64(&request)
 }
 
+/// /ei/get_periodicals
+pub fn build_periodicals_request(ei: String) -> String {
+    let request = proto::GetPeriodicalsRequest {
+        rinfo: build_basic_info(Some(ei.clone())),
+        user_id: Some(ei),
+        current_client_version: Some(VERSION_NUM),
+        contracts_unlocked: Some(true),
+        artifacts_unlocked: Some(true),
+        ..Default::default()
+    };
+
+    encode_to_base64(&request)
+}
+
 pub fn decode_data<T: AsRef<[u8]>, Output: prost::Message + std::default::Default>(
     b64_or_raw: T,
     authorized: bool,
@@ -178,6 +192,23 @@ pub async fn request(
     Ok(data)
 }
 
+pub async fn query_periodicals(
+    client: &Client,
+    ei: &str,
+) -> anyhow::Result<proto::PeriodicalsResponse> {
+    let resp = client
+        .post(format!("{API_BACKEND}/ei/get_periodicals"))
+        .form(&HashMap::from([(
+            "data",
+            build_periodicals_request(ei.to_string()),
+        )]))
+        .send()
+        .await?
+        .error_for_status()?;
+
+    decode_data(resp.bytes().await?, true)
+}
+
 pub fn grade_to_big_g(grade: proto::contract::PlayerGrade) -> f64 {
     match grade {
         proto::contract::PlayerGrade::GradeUnset => 1.0,
@@ -232,3 +263,202 @@ pub(crate) fn extract_epic_research(items: &[ResearchItem]) -> Option<serde_json
         "v": 2,
     }))
 }
+
+fn title_case(s: &str) -> String {
+    s.split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_string() + &chars.as_str().to_lowercase())
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub(crate) fn artifact_name_to_str(name: proto::artifact_spec::Name) -> String {
+    title_case(name.as_str_name())
+}
+
+pub(crate) fn artifact_friendly_name(spec: &proto::ArtifactSpec) -> String {
+    let rarity = spec.rarity();
+    format!(
+        "{} T{}{}",
+        artifact_name_to_str(spec.name()),
+        spec.level() as i32 + 1,
+        if rarity == proto::artifact_spec::Rarity::Common {
+            String::new()
+        } else {
+            format!(" ({})", title_case(rarity.as_str_name()))
+        }
+    )
+}
+
+fn inventory_summary(db: &proto::ArtifactsDb) -> HashMap<proto::ArtifactSpec, f64> {
+    let mut summary = HashMap::new();
+    for item in db.inventory_items.iter().chain(
+        db.virtue_afx_db
+            .iter()
+            .flat_map(|virtue| virtue.inventory_items.iter()),
+    ) {
+        let Some(spec) = item.artifact.as_ref().and_then(|artifact| artifact.spec) else {
+            continue;
+        };
+        *summary.entry(spec).or_insert(0.0) += item.quantity();
+    }
+    summary
+}
+
+/// Items which quantity increased between two snapshots, best rarity first
+pub(crate) fn inventory_drops(
+    previous: &proto::ArtifactsDb,
+    current: &proto::ArtifactsDb,
+) -> Vec<(proto::ArtifactSpec, u64)> {
+    let previous = inventory_summary(previous);
+    let mut drops = inventory_summary(current)
+        .into_iter()
+        .filter_map(|(spec, quantity)| {
+            let diff = quantity - previous.get(&spec).copied().unwrap_or_default();
+            (diff >= 1.0).then_some((spec, diff.round() as u64))
+        })
+        .collect::<Vec<_>>();
+    drops.sort_by_key(|(spec, _)| {
+        (
+            std::cmp::Reverse(spec.rarity()),
+            spec.name(),
+            std::cmp::Reverse(spec.level()),
+        )
+    });
+    drops
+}
+
+pub(crate) fn grade_to_str(grade: proto::contract::PlayerGrade) -> &'static str {
+    grade
+        .as_str_name()
+        .strip_prefix("GRADE_")
+        .unwrap_or(grade.as_str_name())
+}
+
+pub(crate) fn reward_type_to_str(reward_type: proto::RewardType) -> String {
+    title_case(reward_type.as_str_name())
+}
+
+pub(crate) fn egg_name_to_str(egg: proto::Egg) -> String {
+    title_case(egg.as_str_name())
+}
+
+pub(crate) fn parse_spaceship(s: &str) -> Option<proto::mission_info::Spaceship> {
+    let normalize = |s: &str| {
+        s.chars()
+            .filter(|c| c.is_alphanumeric())
+            .collect::<String>()
+            .to_lowercase()
+    };
+    let input = normalize(s);
+    (0..)
+        .map_while(|index| proto::mission_info::Spaceship::try_from(index).ok())
+        .find(|ship| {
+            normalize(SpaceShipInfo::ship_friendly_name(*ship)) == input
+                || normalize(ship.as_str_name()) == input
+        })
+}
+
+pub(crate) fn parse_duration_type(s: &str) -> Option<proto::mission_info::DurationType> {
+    proto::mission_info::DurationType::from_str_name(&s.to_uppercase())
+}
+
+pub(crate) fn decode_backup(cache: &str) -> anyhow::Result<proto::Backup> {
+    decode_data(cache, false)
+}
+
+fn epic_research_level(items: &[ResearchItem], name: &str) -> f64 {
+    items
+        .iter()
+        .find(|x| x.id() == name)
+        .map(|x| x.level() as f64)
+        .unwrap_or_default()
+}
+
+/// Return away time provided by silos in seconds
+pub(crate) fn calc_silo_time(farm_info: &proto::PlayerFarmInfo) -> f64 {
+    let per_silo = 3600.0 + 360.0 * epic_research_level(&farm_info.epic_research, "silo_capacity");
+    farm_info.silos_owned().max(1) as f64 * per_silo
+}
+
+/// Return earning bonus in percent
+pub(crate) fn calc_earning_bonus(game: &proto::backup::Game) -> f64 {
+    let soul_egg_bonus = 10.0 + epic_research_level(&game.epic_research, "soul_eggs");
+    let prophecy_bonus = 1.05 + 0.01 * epic_research_level(&game.epic_research, "prophecy_bonus");
+    game.soul_eggs_d() * soul_egg_bonus * prophecy_bonus.powf(game.eggs_of_prophecy() as f64)
+}
+
+pub(crate) fn earning_bonus_to_soul_power(earning_bonus: f64) -> f64 {
+    (earning_bonus / 100.0).log10()
+}
+
+pub(crate) fn soul_power_to_role(soul_power: f64) -> &'static str {
+    EARNING_BONUS_ROLE
+        .get(soul_power.floor().max(0.0) as usize)
+        .unwrap_or(&DEFAULT_EARNING_BONUS_ROLE)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use proto::artifact_spec::{Level, Name, Rarity};
+
+    fn spec(name: Name, level: Level, rarity: Rarity) -> proto::ArtifactSpec {
+        proto::ArtifactSpec {
+            name: Some(name as i32),
+            level: Some(level as i32),
+            rarity: Some(rarity as i32),
+            ..Default::default()
+        }
+    }
+
+    fn db(items: &[(proto::ArtifactSpec, f64)]) -> proto::ArtifactsDb {
+        proto::ArtifactsDb {
+            inventory_items: items
+                .iter()
+                .map(|(spec, quantity)| proto::ArtifactInventoryItem {
+                    artifact: Some(proto::CompleteArtifact {
+                        spec: Some(*spec),
+                        ..Default::default()
+                    }),
+                    quantity: Some(*quantity),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_inventory_drops() {
+        let totem = spec(Name::LunarTotem, Level::Lesser, Rarity::Common);
+        let medallion = spec(Name::NeodymiumMedallion, Level::Normal, Rarity::Rare);
+        let beak = spec(Name::BeakOfMidas, Level::Inferior, Rarity::Common);
+        let necklace = spec(Name::DemetersNecklace, Level::Greater, Rarity::Legendary);
+        let dust = spec(Name::VialMartianDust, Level::Superior, Rarity::Epic);
+
+        let previous = db(&[(totem, 2.0), (medallion, 1.0), (beak, 3.0), (dust, 1.0)]);
+        let current = db(&[
+            // Increased
+            (totem, 5.0),
+            // Unchanged
+            (medallion, 1.0),
+            // Decreased
+            (beak, 1.0),
+            // New
+            (necklace, 1.0),
+            (dust, 2.0),
+        ]);
+
+        assert_eq!(
+            inventory_drops(&previous, &current),
+            vec![(necklace, 1), (dust, 1), (totem, 3)]
+        );
+        assert!(inventory_drops(&current, &current).is_empty());
+    }
+}
@@ -1,7 +1,9 @@
 mod contract;
+mod periodical;
 mod rocket;
 
 pub(crate) use contract::{
     ContractSubscriber, ContractSubscriberHelper, LAST_QUERY as LAST_SUBSCRIBE_QUERY,
 };
+pub(crate) use periodical::PeriodicalMonitor;
 pub(crate) use rocket::{LAST_QUERY, Monitor, MonitorHelper};
@@ -13,12 +13,12 @@ use tokio::{task::JoinHandle, time::interval};
 
 use crate::CACHE_REQUEST_OFFSET;
 use crate::bot::replace_all;
-use crate::database::types::{ContractSpec, SubscribeInfo, convert_set};
-use crate::egg::coop::{CoopResult, calc_score};
+use crate::database::types::{ContractSpec, SubscribeInfo, UserSetting, convert_set};
+use crate::egg::coop::{CoopResult, calc_score, coop_start_time};
 use crate::egg::{encode_to_byte, query_coop_status};
 
 use crate::functions::build_reqwest_client;
-use crate::types::QueryError;
+use crate::types::{QueryError, timestamp_to_string};
 use crate::{CACHE_REFRESH_PERIOD, bot::BotType, database::DatabaseHelper};
 
 pub static LAST_QUERY: LazyLock<AtomicU64> = LazyLock::new(|| AtomicU64::new(0));
@@ -223,6 +223,16 @@ impl ContractSubscriber {
 
         let info = query_coop_status(client, subscribe.id(), subscribe.room(), None).await?;
 
+        // Spec was chosen by current time, use the version valid when this coop started
+        let spec = match coop_start_time(&spec, &info, current_time) {
+            Some(start_time) => database
+                .contract_query_spec_at(subscribe.id().into(), start_time)
+                .await
+                .flatten()
+                .unwrap_or(spec),
+            None => spec,
+        };
+
         let bytes = encode_to_byte(&info);
 
         database
@@ -236,7 +246,8 @@ impl ContractSubscriber {
             )
             .await;
 
-        let score = calc_score(spec, info)?;
+        let name = spec.name().to_string();
+        let score = calc_score(spec, info, None, current_time)?;
 
         let est = match score {
             CoopResult::Normal(score) => {
@@ -245,12 +256,18 @@ impl ContractSubscriber {
                     helper.refresh_cache(false).await;
 
                     for user in subscribe.users() {
+                        let tz = database
+                            .user_setting_query(*user)
+                            .await
+                            .unwrap_or_else(|| UserSetting::new(*user))
+                            .timezone();
                         bot.send_message(
                             ChatId(*user),
                             format!(
-                                "{}/{} update end time: {est}",
-                                subscribe.id(),
-                                subscribe.room()
+                                "{}/{} update end time: {}",
+                                replace_all(&name),
+                                replace_all(subscribe.room()),
+                                replace_all(&timestamp_to_string(current_time + est, tz))
                             ),
                         )
                         .await
@@ -298,7 +315,7 @@ impl ContractSubscriber {
                 .ok_or_else(|| anyhow!("Query contract cache failure"))?;
 
             let Some(spec) = database
-                .contract_query_spec(subscribed.id().into())
+                .contract_query_spec_at(subscribed.id().into(), current_time as f64)
                 .await
                 .ok_or_else(|| anyhow!("Query contract spec failure"))?
             else {
@@ -313,6 +330,7 @@ impl ContractSubscriber {
                 continue;
             }
 
+            let name = spec.name().to_string();
             let is_err =
                 Self::handle_each_contract(&client, spec, &subscribed, &database, &bot, &helper)
                     .await
@@ -323,7 +341,11 @@ impl ContractSubscriber {
                 for user in subscribed.users() {
                     bot.send_message(
                         ChatId(*user),
-                        format!("Query {}/{} Error", subscribed.id(), subscribed.room()),
+                        format!(
+                            "Query {}/{} Error",
+                            replace_all(&name),
+                            replace_all(subscribed.room())
+                        ),
                     )
                     .await
                     .inspect_err(|e| log::error!("Send message to user {user} error: {e:?}"))
@@ -398,6 +420,13 @@ impl ContractSubscriber {
                 .push(contract.room());
         }
 
+        let mut names = HashMap::new();
+        for id in remap.keys() {
+            if let Some(spec) = database.contract_query_spec(id.to_string()).await.flatten() {
+                names.insert(*id, spec.name().to_string());
+            }
+        }
+
         let mut msg_map = HashMap::new();
 
         for (user, contracts) in pending {
@@ -408,7 +437,12 @@ impl ContractSubscriber {
                     .map(|info| {
                         format!(
                             "{}/{} is finished",
-                            replace_all(info.id()),
+                            replace_all(
+                                names
+                                    .get(info.id())
+                                    .map(String::as_str)
+                                    .unwrap_or(info.id())
+                            ),
                             replace_all(info.room())
                         )
                     })
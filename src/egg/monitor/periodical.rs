@@ -0,0 +1,179 @@
+use std::time::Duration;
+
+use chrono::TimeDelta;
+use itertools::Itertools as _;
+use kstool_helper_generator::Helper;
+use teloxide::types::ChatId;
+use tokio::{task::JoinHandle, time::interval};
+
+use crate::bot::replace_all;
+use crate::database::types::{ContractSeason, ContractSpec};
+use crate::egg::{grade_to_str, parse_num_with_unit, proto, query_periodicals};
+use crate::functions::build_reqwest_client;
+use crate::types::fmt_time_delta_short;
+use crate::{ANNOUNCE_WINDOW, PERIODICAL_CHECK_PERIOD, bot::BotType, database::DatabaseHelper};
+
+use super::Monitor;
+
+#[derive(Clone, Debug, Helper)]
+pub enum PeriodicalMonitorEvent {
+    Exit,
+}
+
+pub struct PeriodicalMonitor {
+    handle: JoinHandle<anyhow::Result<()>>,
+}
+
+impl PeriodicalMonitor {
+    pub fn create(database: DatabaseHelper, bot: BotType) -> (Self, PeriodicalMonitorHelper) {
+        let (s, r) = PeriodicalMonitorHelper::new(4);
+        (
+            Self {
+                handle: tokio::spawn(Self::run(database, r, bot)),
+            },
+            s,
+        )
+    }
+
+    async fn run(
+        database: DatabaseHelper,
+        mut helper: PeriodicalMonitorEventReceiver,
+        bot: BotType,
+    ) -> anyhow::Result<()> {
+        let mut query_timer = interval(Duration::from_secs(PERIODICAL_CHECK_PERIOD));
+
+        loop {
+            tokio::select! {
+                Some(event) = helper.recv() => {
+                    match event {
+                        PeriodicalMonitorEvent::Exit => break,
+                    }
+                }
+
+                _ = query_timer.tick() => {
+                    Self::query(&database, &bot).await
+                        .inspect_err(|e| log::error!("Periodicals query error: {e:?}"))
+                        .ok();
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn contract_text(contract: &proto::Contract) -> String {
+        format!(
+            "New contract dropped: *{}* \\(`{}`\\)\n{}",
+            replace_all(contract.name()),
+            replace_all(contract.identifier()),
+            contract
+                .grade_specs
+                .iter()
+                .sorted_by_key(|spec| std::cmp::Reverse(spec.grade()))
+                .map(|spec| format!(
+                    "{}: {} in {}",
+                    grade_to_str(spec.grade()),
+                    spec.goals
+                        .iter()
+                        .map(
+                            |goal| replace_all(&parse_num_with_unit(goal.target_amount()))
+                                .to_string()
+                        )
+                        .join(" / "),
+                    fmt_time_delta_short(TimeDelta::seconds(spec.length_seconds() as i64))
+                ))
+                .join("\n")
+        )
+    }
+
+    fn event_text(event: &proto::EggIncEvent) -> String {
+        format!(
+            "*{}x {}* event live: {}, ends in {}",
+            replace_all(&event.multiplier().to_string()),
+            replace_all(event.r#type()),
+            replace_all(event.subtitle()),
+            fmt_time_delta_short(TimeDelta::seconds(event.seconds_remaining() as i64))
+        )
+    }
+
+    async fn query(database: &DatabaseHelper, bot: &BotType) -> anyhow::Result<()> {
+        let Some(account) = database
+            .account_query(None)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .find(|account| !account.disabled())
+        else {
+            return Ok(());
+        };
+
+        let periodicals = query_periodicals(&build_reqwest_client(), account.ei()).await?;
+        let current_time = kstool::time::get_current_second() as i64;
+
+        let mut announces = Vec::new();
+
+        if let Some(contracts) = &periodicals.contracts {
+            if let Some(info) = &contracts.current_season {
+                database
+                    .contract_season_insert(ContractSeason::new(
+                        info,
+                        contracts
+                            .contracts
+                            .iter()
+                            .filter(|contract| contract.season_id() == info.id())
+                            .map(|contract| contract.identifier().to_string())
+                            .collect(),
+                    ))
+                    .await;
+            }
+            for contract in &contracts.contracts {
+                database
+                    .contract_spec_insert(ContractSpec::from(contract))
+                    .await;
+                if database
+                    .periodical_seen_insert("contract".into(), contract.identifier().to_string())
+                    .await
+                    .unwrap_or(false)
+                    && contract.start_time() as i64 > current_time - ANNOUNCE_WINDOW
+                {
+                    announces.push(Self::contract_text(contract));
+                }
+            }
+        }
+
+        for event in periodicals
+            .events
+            .iter()
+            .flat_map(|events| events.events.iter())
+        {
+            if database
+                .periodical_seen_insert("event".into(), event.identifier().to_string())
+                .await
+                .unwrap_or(false)
+                && event.start_time() as i64 > current_time - ANNOUNCE_WINDOW
+            {
+                announces.push(Self::event_text(event));
+            }
+        }
+
+        if announces.is_empty() {
+            return Ok(());
+        }
+
+        for setting in database.user_announce_query().await.unwrap_or_default() {
+            let user = ChatId(setting.id());
+            for text in &announces {
+                Monitor::send_or_hold(database, bot, user, &setting, text.clone())
+                    .await
+                    .inspect_err(|e| log::error!("Send message to user {} error: {e:?}", user.0))
+                    .ok();
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn join(self) -> anyhow::Result<()> {
+        self.handle.await?
+    }
+}
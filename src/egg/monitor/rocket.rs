@@ -15,18 +15,27 @@ use tokio::{task::JoinHandle, time::interval};
 
 use crate::CACHE_REQUEST_OFFSET;
 use crate::bot::replace_all;
-use crate::database::types::{Account, AccountMap, ContractSpec, SpaceShip, convert_set};
+use crate::database::types::{
+    Account, AccountGrowth, AccountMap, ContractHistory, ContractSpec, SpaceShip, UserSetting,
+    convert_set,
+};
 
 use crate::functions::build_reqwest_client;
-use crate::types::{QueryError, fmt_time_delta_short, timestamp_to_string};
+use crate::types::{QueryError, fmt_time_delta_short, timestamp_fmt, timestamp_to_string};
 use crate::{
-    CACHE_REFRESH_PERIOD, CHECK_PERIOD, FETCH_PERIOD, bot::BotType, database::DatabaseHelper,
+    CACHE_REFRESH_PERIOD, CHECK_PERIOD, FETCH_PERIOD, MAX_MESSAGE_LENGTH, MAX_REMINDER_MINUTES,
+    MISSION_SLOTS, REMINDER_CHECK_PERIOD, STALE_BACKUP_THRESHOLD, bot::BotType,
+    database::DatabaseHelper,
 };
 
 use crate::egg::{
-    functions::{decode_data, get_missions, parse_num_with_unit, request},
+    functions::{
+        artifact_friendly_name, calc_earning_bonus, decode_backup, decode_data,
+        earning_bonus_to_soul_power, encode_to_base64, get_missions, inventory_drops,
+        parse_num_with_unit, request, soul_power_to_role,
+    },
     proto::ContractCoopStatusResponse,
-    types::ContractGradeSpec,
+    types::{FuelTank, SpaceShipInfo},
 };
 
 pub static LAST_QUERY: LazyLock<AtomicU64> = LazyLock::new(|| AtomicU64::new(0));
@@ -98,6 +107,8 @@ impl Monitor {
         let mut notify_timer = interval(Duration::from_secs(3));
         let mut clear_timer = interval(Duration::from_secs(43200));
         let mut cache_refresh_timer = interval(Duration::from_secs(CACHE_REFRESH_PERIOD));
+        let mut reminder_timer = interval(Duration::from_secs(REMINDER_CHECK_PERIOD));
+        let mut pending_timer = interval(Duration::from_secs(60));
         clear_timer.reset();
 
         let mut cache = BTreeMap::new();
@@ -146,6 +157,18 @@ impl Monitor {
                         .ok();
                 }
 
+                _ = reminder_timer.tick() => {
+                    Self::remind(&database, &bot).await
+                        .inspect_err(|e| log::error!("Remind error: {e:?}"))
+                        .ok();
+                }
+
+                _ = pending_timer.tick() => {
+                    Self::flush_pending(&database, &bot).await
+                        .inspect_err(|e| log::error!("Flush pending message error: {e:?}"))
+                        .ok();
+                }
+
                 _ = cache_refresh_timer.tick() => {
                     Self::refresh_cache(&mut cache, &database).await
                         .inspect_err(|e| log::error!("Refresh cache error: {e:?}"))
@@ -194,6 +217,349 @@ impl Monitor {
         }
     }
 
+    async fn check_idle_slots(
+        current_time: i64,
+        account: &Account,
+        active: usize,
+        database: &DatabaseHelper,
+        account_map: &AccountMap,
+        bot: &BotType,
+    ) {
+        if active >= MISSION_SLOTS {
+            if account.idle_since().is_some() {
+                database
+                    .account_idle_update(account.ei().to_string(), None)
+                    .await;
+            }
+            return;
+        }
+
+        let Some(idle_since) = account.idle_since() else {
+            database
+                .account_idle_update(account.ei().to_string(), Some(current_time))
+                .await;
+            return;
+        };
+
+        for user in account_map.chat_ids() {
+            let setting = database
+                .user_setting_query(user.0)
+                .await
+                .unwrap_or_else(|| UserSetting::new(user.0));
+            let Some(threshold) = setting.idle_threshold() else {
+                continue;
+            };
+            // Only nag once, when threshold passed between last fetch and this fetch
+            let deadline = idle_since + threshold * 60;
+            if deadline <= account.last_fetch() || deadline > current_time {
+                continue;
+            }
+            let empty = MISSION_SLOTS - active;
+            Self::send_or_hold(
+                database,
+                bot,
+                user,
+                &setting,
+                format!(
+                    "You have {empty} empty mission slot{} on *{}*",
+                    if empty > 1 { "s" } else { "" },
+                    replace_all(account.name())
+                ),
+            )
+            .await
+            .inspect_err(|e| log::error!("Send message to user {} error: {e:?}", user.0))
+            .ok();
+        }
+    }
+
+    /// Store backup snapshot, report new inventory items if tracked spaceship was collected
+    async fn report_drops(
+        account: &Account,
+        backup: &crate::egg::proto::Backup,
+        database: &DatabaseHelper,
+        account_map: &AccountMap,
+        bot: &BotType,
+    ) {
+        let previous = database
+            .account_cache_query(account.ei().to_string())
+            .await
+            .flatten()
+            .and_then(|cache| {
+                decode_backup(cache.cache())
+                    .inspect_err(|e| log::warn!("Decode {} cache error: {e:?}", account.ei()))
+                    .ok()
+            });
+        database
+            .account_cache_insert(account.ei().to_string(), encode_to_base64(backup))
+            .await;
+
+        let (Some(previous), Some(current)) = (
+            previous.and_then(|backup| backup.artifacts_db),
+            backup.artifacts_db.as_ref(),
+        ) else {
+            return;
+        };
+
+        let archived = previous
+            .mission_archive
+            .iter()
+            .map(|mission| mission.identifier())
+            .collect::<HashSet<_>>();
+        let mut returned = Vec::new();
+        for mission in &current.mission_archive {
+            if archived.contains(mission.identifier()) {
+                continue;
+            }
+            if database
+                .mission_single_query(mission.identifier().to_string())
+                .await
+                .flatten()
+                .is_some()
+            {
+                returned.push(SpaceShipInfo::ship_friendly_name(mission.ship()));
+            }
+        }
+        if returned.is_empty() {
+            return;
+        }
+
+        let drops = inventory_drops(&previous, current);
+        if drops.is_empty() {
+            return;
+        }
+
+        let text = format!(
+            "*{}* {} brought back:\n{}",
+            replace_all(account.name()),
+            replace_all(&returned.join(", ")),
+            drops
+                .iter()
+                .map(|(spec, quantity)| format!(
+                    "{} x{quantity}",
+                    replace_all(&artifact_friendly_name(spec))
+                ))
+                .join("\n")
+        );
+        for user in account_map.chat_ids() {
+            let setting = database
+                .user_setting_query(user.0)
+                .await
+                .unwrap_or_else(|| UserSetting::new(user.0));
+            Self::send_or_hold(database, bot, user, &setting, text.clone())
+                .await
+                .inspect_err(|e| log::error!("Send message to user {} error: {e:?}", user.0))
+                .ok();
+        }
+    }
+
+    async fn check_fuel(
+        account: &Account,
+        backup: &crate::egg::proto::Backup,
+        database: &DatabaseHelper,
+        account_map: &AccountMap,
+        bot: &BotType,
+    ) {
+        let Some(tank) = FuelTank::from_backup(backup) else {
+            return;
+        };
+        for user in account_map.chat_ids() {
+            let setting = database
+                .user_setting_query(user.0)
+                .await
+                .unwrap_or_else(|| UserSetting::new(user.0));
+            let Some((ship, duration_type)) = setting.fuel_alert() else {
+                continue;
+            };
+            let ready = FuelTank::requirement(backup, ship, duration_type)
+                .is_some_and(|requirement| tank.is_enough(&requirement));
+            if !database
+                .fuel_notified_update(user.0, account.ei().to_string(), ready)
+                .await
+                .unwrap_or(false)
+            {
+                continue;
+            }
+            Self::send_or_hold(
+                database,
+                bot,
+                user,
+                &setting,
+                format!(
+                    "*{}* has enough fuel for {} {}",
+                    replace_all(account.name()),
+                    replace_all(SpaceShipInfo::ship_friendly_name(ship)),
+                    SpaceShip::duration_type_to_str(duration_type as i64)
+                ),
+            )
+            .await
+            .inspect_err(|e| log::error!("Send message to user {} error: {e:?}", user.0))
+            .ok();
+        }
+    }
+
+    async fn check_backup_time(
+        current_time: i64,
+        account: &Account,
+        backup: &crate::egg::proto::Backup,
+        database: &DatabaseHelper,
+        account_map: &AccountMap,
+        bot: &BotType,
+    ) {
+        let backup_time = backup.approx_time() as i64;
+        if backup_time <= 0 {
+            return;
+        }
+        database
+            .account_backup_time_update(account.ei().to_string(), backup_time)
+            .await;
+
+        let delta = current_time - backup_time;
+        if delta <= STALE_BACKUP_THRESHOLD || account.is_stale() {
+            return;
+        }
+
+        for user in account_map.chat_ids() {
+            let setting = database
+                .user_setting_query(user.0)
+                .await
+                .unwrap_or_else(|| UserSetting::new(user.0));
+            Self::send_or_hold(
+                database,
+                bot,
+                user,
+                &setting,
+                format!(
+                    "*{}* backup is {} old, mission times may be outdated\\. Open the game to sync\\.",
+                    replace_all(account.name()),
+                    fmt_time_delta_short(TimeDelta::seconds(delta))
+                ),
+            )
+            .await
+            .inspect_err(|e| log::error!("Send message to user {} error: {e:?}", user.0))
+            .ok();
+        }
+    }
+
+    async fn check_piggy_and_gift(
+        current_time: i64,
+        account: &Account,
+        backup: &crate::egg::proto::Backup,
+        database: &DatabaseHelper,
+        account_map: &AccountMap,
+        bot: &BotType,
+    ) {
+        let mut events = Vec::new();
+        if let Some(stats) = &backup.stats
+            && stats.piggy_full()
+        {
+            events.push((
+                "piggy",
+                (stats.time_piggy_filled_realtime() as i64).to_string(),
+                format!("Piggy bank is full on *{}*", replace_all(account.name())),
+            ));
+        }
+        if let Some(game) = &backup.game {
+            let next_gift = game.next_daily_gift_time() as i64;
+            if next_gift > 0 && next_gift <= current_time {
+                events.push((
+                    "gift",
+                    next_gift.to_string(),
+                    format!(
+                        "Daily gift not collected on *{}*",
+                        replace_all(account.name())
+                    ),
+                ));
+            }
+        }
+
+        for (kind, key, text) in events {
+            for user in account_map.chat_ids() {
+                let setting = database
+                    .user_setting_query(user.0)
+                    .await
+                    .unwrap_or_else(|| UserSetting::new(user.0));
+                let enabled = match kind {
+                    "piggy" => setting.piggy_alert(),
+                    _ => setting.gift_alert(),
+                };
+                // Record per user, so user enabled alert later still gets notified
+                if !enabled
+                    || !database
+                        .account_notification_update(
+                            user.0,
+                            account.ei().to_string(),
+                            kind.to_string(),
+                            key.clone(),
+                        )
+                        .await
+                        .unwrap_or(false)
+                {
+                    continue;
+                }
+                Self::send_or_hold(database, bot, user, &setting, text.clone())
+                    .await
+                    .inspect_err(|e| log::error!("Send message to user {} error: {e:?}", user.0))
+                    .ok();
+            }
+        }
+    }
+
+    async fn record_growth(
+        current_time: i64,
+        account: &Account,
+        backup: &crate::egg::proto::Backup,
+        database: &DatabaseHelper,
+        account_map: &AccountMap,
+        bot: &BotType,
+    ) {
+        let Some(game) = &backup.game else {
+            return;
+        };
+        let earning_bonus = calc_earning_bonus(game);
+        let Some(previous) = database
+            .account_growth_insert(AccountGrowth::new(
+                account.ei().to_string(),
+                current_time,
+                game.soul_eggs_d(),
+                game.eggs_of_prophecy() as i64,
+                earning_bonus,
+            ))
+            .await
+            .flatten()
+        else {
+            return;
+        };
+
+        let role = soul_power_to_role(earning_bonus_to_soul_power(earning_bonus));
+        if earning_bonus <= previous.earning_bonus()
+            || role == soul_power_to_role(earning_bonus_to_soul_power(previous.earning_bonus()))
+        {
+            return;
+        }
+
+        for user in account_map.chat_ids() {
+            let setting = database
+                .user_setting_query(user.0)
+                .await
+                .unwrap_or_else(|| UserSetting::new(user.0));
+            Self::send_or_hold(
+                database,
+                bot,
+                user,
+                &setting,
+                format!(
+                    "Congratulations\\! *{}* is now *{}* with {}% earning bonus",
+                    replace_all(account.name()),
+                    replace_all(role),
+                    replace_all(&parse_num_with_unit(earning_bonus))
+                ),
+            )
+            .await
+            .inspect_err(|e| log::error!("Send message to user {} error: {e:?}", user.0))
+            .ok();
+        }
+    }
+
     async fn check_username(
         account: &Account,
         database: &DatabaseHelper,
@@ -223,11 +589,75 @@ impl Monitor {
         Ok(())
     }
 
+    async fn warn_unfinalized(
+        current_time: i64,
+        account: &Account,
+        local_contract: &crate::egg::proto::LocalContract,
+        database: &DatabaseHelper,
+        account_map: &AccountMap,
+        bot: &BotType,
+    ) {
+        let shared_end = local_contract.coop_shared_end_time() as i64;
+        let grace_end = local_contract.coop_grace_period_end_time() as i64;
+        if local_contract.coop_contribution_finalized()
+            || shared_end <= 0
+            || shared_end > current_time
+            || grace_end <= current_time
+        {
+            return;
+        }
+        let Some(contract) = &local_contract.contract else {
+            return;
+        };
+        for user in account_map.chat_ids() {
+            if !database
+                .account_notification_update(
+                    user.0,
+                    account.ei().to_string(),
+                    "finalize".into(),
+                    format!(
+                        "{}:{}",
+                        contract.identifier(),
+                        local_contract.coop_identifier()
+                    ),
+                )
+                .await
+                .unwrap_or(false)
+            {
+                continue;
+            }
+            let setting = database
+                .user_setting_query(user.0)
+                .await
+                .unwrap_or_else(|| UserSetting::new(user.0));
+            Self::send_or_hold(
+                database,
+                bot,
+                user,
+                &setting,
+                format!(
+                    "*{}* hasn't finalized contribution to `{}` \\(`{}`\\), {} left before grace period ends",
+                    replace_all(account.name()),
+                    replace_all(contract.identifier()),
+                    replace_all(local_contract.coop_identifier()),
+                    fmt_time_delta_short(TimeDelta::seconds(grace_end - current_time))
+                ),
+            )
+            .await
+            .inspect_err(|e| log::error!("Send message to user {} error: {e:?}", user.0))
+            .ok();
+        }
+    }
+
     async fn inject_contracts(
-        ei: &str,
+        current_time: i64,
+        account: &Account,
         database: &DatabaseHelper,
+        account_map: &AccountMap,
+        bot: &BotType,
         info: &crate::egg::proto::EggIncFirstContactResponse,
     ) -> Option<()> {
+        let ei = account.ei();
         /* {
             let file = tokio::fs::OpenOptions::new()
                 .create(true)
@@ -246,22 +676,37 @@ impl Monitor {
 
         let contracts = backup.contracts.as_ref()?;
 
+        for local_contract in &contracts.contracts {
+            Self::warn_unfinalized(
+                current_time,
+                account,
+                local_contract,
+                database,
+                account_map,
+                bot,
+            )
+            .await;
+        }
+
+        for evaluation in contracts
+            .archive
+            .iter()
+            .filter_map(|local_contract| local_contract.evaluation.as_ref())
+        {
+            database
+                .contract_history_insert(ContractHistory::from_evaluation(
+                    ei.to_string(),
+                    evaluation,
+                ))
+                .await;
+        }
+
         for local_contract in contracts.contracts.iter().chain(contracts.archive.iter()) {
             let Some(ref contract) = local_contract.contract else {
                 continue;
             };
-            let v = contract
-                .grade_specs
-                .iter()
-                .map(ContractGradeSpec::from)
-                .collect_vec();
             database
-                .contract_spec_insert(ContractSpec::new(
-                    contract.identifier().to_string(),
-                    contract.max_coop_size() as i64,
-                    contract.minutes_per_token(),
-                    v,
-                ))
+                .contract_spec_insert(ContractSpec::from(contract))
                 .await;
             if database
                 .account_insert_contract(
@@ -334,11 +779,13 @@ impl Monitor {
                     contract.cleared_for_exit() || contract.all_members_reporting(),
                 )
                 .await;
-            if let Some(spec) = database
-                .contract_query_spec(contract.contract_identifier().into())
+            let contract_spec = database
+                .contract_query_spec_at(contract.contract_identifier().into(), backup_timestamp)
                 .await
-                .flatten()
-                && let Some(spec) = spec.get(&contract.grade())
+                .flatten();
+            if let Some(spec) = contract_spec
+                .as_ref()
+                .and_then(|spec| spec.get(&contract.grade()))
             {
                 database
                     .contract_start_time_update(
@@ -353,7 +800,11 @@ impl Monitor {
                 continue;
             }
             log_output.push(format!(
-                "{}: {}{}",
+                "{} ({}): {}{}",
+                contract_spec
+                    .as_ref()
+                    .map(|spec| spec.name())
+                    .unwrap_or(contract.contract_identifier()),
                 contract.contract_identifier(),
                 contract.coop_identifier(),
                 if contract.cleared_for_exit() {
@@ -386,8 +837,13 @@ impl Monitor {
                 .into_iter()
                 .filter(|s| !s.notified())
                 .count()
-                >= 3
+                >= MISSION_SLOTS
         {
+            if account.idle_since().is_some() {
+                database
+                    .account_idle_update(account.ei().to_string(), None)
+                    .await;
+            }
             return Ok(false);
         };
 
@@ -399,9 +855,18 @@ impl Monitor {
         let info = request(client, account.ei(), None).await?;
 
         if account.contract_trace() {
-            Self::inject_contracts(account.ei(), database, &info).await;
+            Self::inject_contracts(current_time, account, database, &account_map, bot, &info).await;
         }
         Self::check_username(account, database, &account_map, bot, &info.backup).await?;
+        if let Some(backup) = &info.backup {
+            Self::check_backup_time(current_time, account, backup, database, &account_map, bot)
+                .await;
+            Self::report_drops(account, backup, database, &account_map, bot).await;
+            Self::check_fuel(account, backup, database, &account_map, bot).await;
+            Self::record_growth(current_time, account, backup, database, &account_map, bot).await;
+            Self::check_piggy_and_gift(current_time, account, backup, database, &account_map, bot)
+                .await;
+        }
 
         let Some(missions) = get_missions(info) else {
             return Err(anyhow!("Player {} missions field is missing", account.ei()).into());
@@ -410,37 +875,31 @@ impl Monitor {
 
         //log::trace!("{}({}) missions {missions:?}", account.name(), account.ei());
 
+        let active = missions.iter().filter(|s| !s.is_landed()).count();
+        Self::check_idle_slots(current_time, account, active, database, &account_map, bot).await;
+
         let mut pending = Vec::new();
 
         for mission in missions {
-            if mission.is_landed() {
-                continue;
-            }
-            if database
+            if let Some(spaceship) = database
                 .mission_single_query(mission.id().to_string())
                 .await
                 .flatten()
-                .is_some()
             {
+                if spaceship.status_code() != mission.status() {
+                    database
+                        .mission_status_update(mission.id().to_string(), mission.status())
+                        .await;
+                }
+                continue;
+            }
+            if mission.is_landed() {
                 continue;
             }
             database
-                .mission_add(
-                    mission.id().to_string(),
-                    mission.name().to_string(),
-                    mission.duration_type(),
-                    account.ei().to_string(),
-                    mission.land(),
-                )
+                .mission_add(account.ei().to_string(), mission.clone())
                 .await;
-            pending.push(format!(
-                "{} \\[{}\\] \\(_{}_\\), launch time: {}, land time: {}",
-                replace_all(mission.name()),
-                SpaceShip::duration_type_to_str(mission.duration_type()),
-                replace_all(mission.id()),
-                replace_all(&timestamp_to_string(mission.launched())),
-                replace_all(&timestamp_to_string(mission.land()))
-            ));
+            pending.push(mission);
         }
 
         if pending.is_empty() {
@@ -450,12 +909,36 @@ impl Monitor {
         helper.refresh_cache(false).await;
 
         for user in account_map.chat_ids() {
-            bot.send_message(
+            let setting = database
+                .user_setting_query(user.0)
+                .await
+                .unwrap_or_else(|| UserSetting::new(user.0));
+            let tz = setting.timezone();
+            Self::send_or_hold(
+                database,
+                bot,
                 user,
+                &setting,
                 format!(
                     "*{}* Found new spaceship:\n{}",
                     replace_all(account.name()),
-                    pending.join("\n")
+                    pending
+                        .iter()
+                        .map(|mission| format!(
+                            "{} \\[{}\\] {} \\(_{}_\\), launch time: {}, land time: {}",
+                            replace_all(mission.name()),
+                            SpaceShip::duration_type_to_str(mission.duration_type()),
+                            replace_all(&SpaceShip::detail(
+                                mission.level(),
+                                mission.capacity(),
+                                mission.target(),
+                                mission.mission_type()
+                            )),
+                            replace_all(mission.id()),
+                            replace_all(&timestamp_to_string(mission.launched(), tz)),
+                            replace_all(&timestamp_to_string(mission.land(), tz))
+                        ))
+                        .join("\n")
                 ),
             )
             .await
@@ -639,22 +1122,288 @@ impl Monitor {
         }
 
         for (player, msg) in &msg_map {
-            if let Err(e) = bot.send_message(*player, msg.join("\n\n")).await {
+            let setting = database
+                .user_setting_query(player.0)
+                .await
+                .unwrap_or_else(|| UserSetting::new(player.0));
+            if let Err(e) =
+                Self::send_or_hold(database, bot, *player, &setting, msg.join("\n\n")).await
+            {
                 log::error!("Send message to user {} error: {e:?}", player.0);
-                if Self::is_user_unreachable(&e) {
-                    if let Some(eis) = user_eis.get(player) {
-                        for ei in eis {
-                            log::warn!("User {} is unreachable, removing from {ei}", player.0);
-                            database.user_remove_account(player.0, ei.clone()).await;
-                        }
+                if Self::is_user_unreachable(&e)
+                    && let Some(eis) = user_eis.get(player)
+                {
+                    for ei in eis {
+                        log::warn!("User {} is unreachable, removing from {ei}", player.0);
+                        database.user_remove_account(player.0, ei.clone()).await;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn remind(database: &DatabaseHelper, bot: &BotType) -> anyhow::Result<()> {
+        let current_time = kstool::time::get_current_second() as i64;
+        let missions = database
+            .mission_query_upcoming(current_time + MAX_REMINDER_MINUTES * 60)
+            .await
+            .ok_or_else(|| anyhow!("Query upcoming mission error"))?;
+        if missions.is_empty() {
+            return Ok(());
+        }
+
+        let mut pending = HashMap::new();
+        for mission in &missions {
+            pending
+                .entry(mission.belong())
+                .or_insert_with(Vec::new)
+                .push(mission);
+        }
+
+        let mut reminders: HashMap<ChatId, Vec<i64>> = HashMap::new();
+        let mut msg_map: HashMap<ChatId, Vec<String>> = HashMap::new();
+
+        for (ei, missions) in pending {
+            let Some(account) = database
+                .account_query_ei(ei.to_string())
+                .await
+                .tap_none(|| log::error!("Query database players {ei} error"))
+                .flatten()
+            else {
+                continue;
+            };
+
+            let Some(account_map) = database
+                .account_query_users(ei.to_string())
+                .await
+                .tap_none(|| log::error!("Query database map {ei} error"))
+            else {
+                continue;
+            };
+
+            for user in account_map.chat_ids() {
+                if let std::collections::hash_map::Entry::Vacant(entry) = reminders.entry(user) {
+                    entry.insert(
+                        database
+                            .user_setting_query(user.0)
+                            .await
+                            // Reminder is meaningless after quiet hours, skip it
+                            .filter(|setting| !setting.is_quiet(current_time))
+                            .map(|setting| setting.reminders())
+                            .unwrap_or_default(),
+                    );
+                }
+            }
+
+            for mission in missions {
+                let remain = mission.land() - current_time;
+                let mut reminded = None;
+
+                for user in account_map.chat_ids() {
+                    // Pick the closest lead time which is already due
+                    let Some(stage) = reminders[&user]
+                        .iter()
+                        .filter(|offset| **offset * 60 >= remain)
+                        .min()
+                        .copied()
+                    else {
+                        continue;
+                    };
+                    if mission.reminded().is_some_and(|r| stage >= r) {
+                        continue;
                     }
+                    reminded = Some(reminded.map_or(stage, |r: i64| r.min(stage)));
+                    msg_map.entry(user).or_default().push(format!(
+                        "*{}*: __{}__ lands in {}",
+                        replace_all(account.name()),
+                        replace_all(mission.name()),
+                        fmt_time_delta_short(TimeDelta::seconds(remain))
+                    ));
+                }
+
+                if let Some(reminded) = reminded {
+                    database
+                        .mission_reminded(mission.id().to_string(), reminded)
+                        .await;
+                }
+            }
+        }
+
+        for (user, msg) in msg_map {
+            bot.send_message(user, format!("Landing soon:\n{}", msg.join("\n")))
+                .await
+                .inspect_err(|e| log::error!("Send message to user {} error: {e:?}", user.0))
+                .ok();
+        }
+        Ok(())
+    }
+
+    /// Send message to user, or hold it in database until user's quiet hours end
+    pub(super) async fn send_or_hold(
+        database: &DatabaseHelper,
+        bot: &BotType,
+        user: ChatId,
+        setting: &UserSetting,
+        text: String,
+    ) -> Result<(), teloxide::RequestError> {
+        let current_time = kstool::time::get_current_second() as i64;
+        if setting.is_quiet(current_time) {
+            database
+                .pending_message_add(
+                    user.0,
+                    format!(
+                        "_{}_ {text}",
+                        timestamp_fmt(current_time, "%H:%M", setting.timezone())
+                    ),
+                )
+                .await;
+            return Ok(());
+        }
+        bot.send_message(user, text).await?;
+        Ok(())
+    }
+
+    async fn flush_pending(database: &DatabaseHelper, bot: &BotType) -> anyhow::Result<()> {
+        let current_time = kstool::time::get_current_second() as i64;
+        let users = database
+            .pending_message_users()
+            .await
+            .ok_or_else(|| anyhow!("Query pending message users error"))?;
+
+        for user in users {
+            let setting = database
+                .user_setting_query(user)
+                .await
+                .unwrap_or_else(|| UserSetting::new(user));
+            if setting.is_quiet(current_time) {
+                continue;
+            }
+            let messages = database
+                .pending_message_query(user)
+                .await
+                .ok_or_else(|| anyhow!("Query pending message error"))?;
+
+            for (ids, text) in Self::split_pending(
+                messages
+                    .iter()
+                    .map(|message| (message.id(), message.text())),
+            ) {
+                if let Err(e) = bot.send_message(ChatId(user), text).await {
+                    // Keep undelivered messages for next flush
+                    log::error!("Send message to user {user} error: {e:?}");
+                    break;
                 }
+                database.pending_message_delete(ids).await;
             }
         }
         Ok(())
     }
 
+    /// Group held messages into digests under Telegram message length limit, a message too long
+    /// for one digest is split by lines and its id goes with its last part
+    fn split_pending<'a>(
+        messages: impl IntoIterator<Item = (i64, &'a str)>,
+    ) -> Vec<(Vec<i64>, String)> {
+        const HEADER: &str = "While you were in quiet hours:";
+        let limit = MAX_MESSAGE_LENGTH - HEADER.chars().count() - 2;
+        let mut ret = vec![];
+        let mut ids = vec![];
+        let mut text = HEADER.to_string();
+        for (id, message) in messages {
+            for part in Self::split_message(message, limit) {
+                if text.len() > HEADER.len()
+                    && text.chars().count() + part.chars().count() + 2 > MAX_MESSAGE_LENGTH
+                {
+                    ret.push((
+                        std::mem::take(&mut ids),
+                        std::mem::replace(&mut text, HEADER.to_string()),
+                    ));
+                }
+                text.push_str("\n\n");
+                text.push_str(&part);
+            }
+            ids.push(id);
+        }
+        if text.len() > HEADER.len() {
+            ret.push((ids, text));
+        }
+        ret
+    }
+
+    // Split text by lines into parts of at most `limit` characters, cut lines longer than that
+    fn split_message(text: &str, limit: usize) -> Vec<String> {
+        let mut ret = vec![];
+        let mut current = String::new();
+        let mut current_len = 0;
+        for line in text.split('\n') {
+            let line = line.chars().collect::<Vec<_>>();
+            for piece in line.chunks(limit).map(|x| x.iter().collect::<String>()) {
+                let piece_len = piece.chars().count();
+                if current_len > 0 && current_len + 1 + piece_len > limit {
+                    ret.push(std::mem::take(&mut current));
+                    current_len = 0;
+                }
+                if current_len > 0 {
+                    current.push('\n');
+                    current_len += 1;
+                }
+                current.push_str(&piece);
+                current_len += piece_len;
+            }
+        }
+        ret.push(current);
+        ret
+    }
+
     pub async fn join(self) -> anyhow::Result<()> {
         self.handle.await?
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const HEADER_LEN: usize = "While you were in quiet hours:".len();
+
+    #[test]
+    fn test_split_pending_short() {
+        let ret = Monitor::split_pending([(1, "a"), (2, "b")]);
+        assert_eq!(ret.len(), 1);
+        assert_eq!(ret[0].0, vec![1, 2]);
+        assert_eq!(ret[0].1, "While you were in quiet hours:\n\na\n\nb");
+        assert!(Monitor::split_pending([]).is_empty());
+    }
+
+    #[test]
+    fn test_split_pending_at_limit() {
+        // Exactly fills one digest
+        let full = "x".repeat(MAX_MESSAGE_LENGTH - HEADER_LEN - 2);
+        let ret = Monitor::split_pending([(1, full.as_str()), (2, "y")]);
+        assert_eq!(ret.len(), 2);
+        assert_eq!(ret[0].0, vec![1]);
+        assert_eq!(ret[0].1.chars().count(), MAX_MESSAGE_LENGTH);
+        assert_eq!(ret[1].0, vec![2]);
+    }
+
+    #[test]
+    fn test_split_pending_long_message() {
+        let line = "#".repeat(1000);
+        let long = [line.as_str(); 10].join("\n");
+        let huge = "@".repeat(MAX_MESSAGE_LENGTH * 2);
+        let ret = Monitor::split_pending([(1, long.as_str()), (2, huge.as_str())]);
+        assert!(
+            ret.iter()
+                .all(|(_, text)| text.chars().count() <= MAX_MESSAGE_LENGTH)
+        );
+        // Each id is delivered once, with the last part of its message
+        assert_eq!(ret.iter().flat_map(|(ids, _)| ids).count(), 2);
+        assert_eq!(ret.last().unwrap().0, vec![2]);
+        let total = ret
+            .iter()
+            .map(|(_, text)| text.chars().filter(|c| *c == '#' || *c == '@').count())
+            .sum::<usize>();
+        assert_eq!(total, 10 * 1000 + MAX_MESSAGE_LENGTH * 2);
+    }
+}
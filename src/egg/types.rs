@@ -7,6 +7,11 @@ pub struct SpaceShipInfo {
     duration_type: i64,
     duration: i64,
     launched: i64,
+    level: i64,
+    capacity: i64,
+    target: Option<i64>,
+    status: i64,
+    mission_type: i64,
 }
 
 impl SpaceShipInfo {
@@ -31,6 +36,21 @@ impl SpaceShipInfo {
     pub fn is_landed(&self) -> bool {
         kstool::time::get_current_second() as i64 > self.land()
     }
+    pub fn level(&self) -> i64 {
+        self.level
+    }
+    pub fn capacity(&self) -> i64 {
+        self.capacity
+    }
+    pub fn target(&self) -> Option<i64> {
+        self.target
+    }
+    pub fn status(&self) -> i64 {
+        self.status
+    }
+    pub fn mission_type(&self) -> i64 {
+        self.mission_type
+    }
 
     pub fn ship_friendly_name(ship: super::proto::mission_info::Spaceship) -> &'static str {
         use super::proto::mission_info::Spaceship;
@@ -59,16 +79,115 @@ impl From<super::proto::MissionInfo> for SpaceShipInfo {
             duration_type: value.duration_type() as i64,
             duration: value.duration_seconds() as i64,
             launched: value.start_time_derived() as i64,
+            level: value.level() as i64,
+            capacity: value.capacity() as i64,
+            target: value
+                .target_artifact
+                .filter(|target| *target != super::proto::artifact_spec::Name::Unknown as i32)
+                .map(|target| target as i64),
+            status: value.status() as i64,
+            mission_type: value.r#type() as i64,
         }
     }
 }
 
+#[derive(Clone, Debug)]
+pub struct FuelTank {
+    capacity: f64,
+    // Egg, amount, limit
+    fuels: Vec<(super::proto::Egg, f64, f64)>,
+}
+
+impl FuelTank {
+    pub fn from_backup(backup: &super::proto::Backup) -> Option<Self> {
+        let artifacts = backup.artifacts.as_ref()?;
+        let capacity = super::definitions::FUEL_TANK_CAPACITY
+            .get(artifacts.tank_level() as usize)
+            .or(super::definitions::FUEL_TANK_CAPACITY.last())
+            .copied()?;
+        Some(Self {
+            capacity,
+            // Index 0 is edible egg
+            fuels: artifacts
+                .tank_fuels
+                .iter()
+                .enumerate()
+                .filter_map(|(index, amount)| {
+                    let egg = super::proto::Egg::try_from(index as i32 + 1).ok()?;
+                    let limit = artifacts.tank_limits.get(index).copied().unwrap_or(1.0);
+                    Some((egg, *amount, limit * capacity))
+                })
+                .collect(),
+        })
+    }
+
+    pub fn capacity(&self) -> f64 {
+        self.capacity
+    }
+
+    pub fn fuels(&self) -> &[(super::proto::Egg, f64, f64)] {
+        &self.fuels
+    }
+
+    pub fn amount(&self, egg: super::proto::Egg) -> f64 {
+        self.fuels
+            .iter()
+            .find(|(e, _, _)| *e == egg)
+            .map(|(_, amount, _)| *amount)
+            .unwrap_or_default()
+    }
+
+    pub fn is_enough(&self, requirement: &[(super::proto::Egg, f64)]) -> bool {
+        requirement
+            .iter()
+            .all(|(egg, amount)| self.amount(*egg) >= *amount)
+    }
+
+    /// Fuel used by the latest mission of this ship and duration
+    pub fn requirement(
+        backup: &super::proto::Backup,
+        ship: super::proto::mission_info::Spaceship,
+        duration_type: super::proto::mission_info::DurationType,
+    ) -> Option<Vec<(super::proto::Egg, f64)>> {
+        let db = backup.artifacts_db.as_ref()?;
+        db.mission_archive
+            .iter()
+            .chain(db.mission_infos.iter())
+            .filter(|mission| {
+                mission.ship() == ship
+                    && mission.duration_type() == duration_type
+                    && !mission.fuel.is_empty()
+            })
+            .max_by(|a, b| a.start_time_derived().total_cmp(&b.start_time_derived()))
+            .map(|mission| {
+                mission
+                    .fuel
+                    .iter()
+                    .map(|fuel| (fuel.egg(), fuel.amount()))
+                    .collect()
+            })
+    }
+}
+
+fn default_modifier() -> f64 {
+    1.0
+}
+
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct ContractGradeSpec {
     grade: i32,
     length: f64,
     goal1: f64,
     goal3: f64,
+    // Only for display, production params reported by coop status already include modifiers
+    #[serde(default = "default_modifier")]
+    elr_modifier: f64,
+    #[serde(default = "default_modifier")]
+    sr_modifier: f64,
+    #[serde(default = "default_modifier")]
+    ihr_modifier: f64,
+    #[serde(default = "default_modifier")]
+    hab_capacity_modifier: f64,
 }
 
 impl ContractGradeSpec {
@@ -76,6 +195,18 @@ impl ContractGradeSpec {
         goal.target_amount()
     }
 
+    fn extract_modifier(
+        value: &super::proto::contract::GradeSpec,
+        dimension: super::proto::game_modifier::GameDimension,
+    ) -> f64 {
+        value
+            .modifiers
+            .iter()
+            .filter(|modifier| modifier.dimension() == dimension)
+            .map(|modifier| modifier.value())
+            .product()
+    }
+
     pub fn into_kv(self) -> (super::proto::contract::PlayerGrade, Self) {
         (
             match self.grade {
@@ -100,6 +231,20 @@ impl ContractGradeSpec {
     pub fn goal3(&self) -> f64 {
         self.goal3
     }
+
+    /// Modifiers are not applied to projections, coop status production params include them
+    pub fn display_modifiers(&self) -> Vec<String> {
+        [
+            ("ELR", self.elr_modifier),
+            ("Shipping", self.sr_modifier),
+            ("IHR", self.ihr_modifier),
+            ("Hab capacity", self.hab_capacity_modifier),
+        ]
+        .into_iter()
+        .filter(|(_, value)| *value != 1.0)
+        .map(|(name, value)| format!("{name} ×{value}"))
+        .collect()
+    }
 }
 
 impl From<&super::proto::contract::GradeSpec> for ContractGradeSpec {
@@ -117,6 +262,22 @@ impl From<&super::proto::contract::GradeSpec> for ContractGradeSpec {
                 .last()
                 .map(Self::extract_goal)
                 .unwrap_or_default(),
+            elr_modifier: Self::extract_modifier(
+                value,
+                super::proto::game_modifier::GameDimension::EggLayingRate,
+            ),
+            sr_modifier: Self::extract_modifier(
+                value,
+                super::proto::game_modifier::GameDimension::ShippingCapacity,
+            ),
+            ihr_modifier: Self::extract_modifier(
+                value,
+                super::proto::game_modifier::GameDimension::InternalHatcheryRate,
+            ),
+            hab_capacity_modifier: Self::extract_modifier(
+                value,
+                super::proto::game_modifier::GameDimension::HabCapacity,
+            ),
         }
     }
 }
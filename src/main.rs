@@ -15,12 +15,25 @@ use database::DatabaseHandle;
 use egg::monitor::Monitor;
 use functions::download_contract;
 
-use crate::egg::monitor::ContractSubscriber;
+use crate::egg::monitor::{ContractSubscriber, PeriodicalMonitor};
 
 static FETCH_PERIOD: OnceLock<i64> = OnceLock::new();
 static CHECK_PERIOD: OnceLock<i64> = OnceLock::new();
 const CACHE_REFRESH_PERIOD: u64 = 300;
 const CACHE_REQUEST_OFFSET: u64 = CACHE_REFRESH_PERIOD * 2;
+const REMINDER_CHECK_PERIOD: u64 = 30;
+const MAX_REMINDER_MINUTES: i64 = 180;
+const MAX_REMINDERS: usize = 3;
+const MISSION_SLOTS: usize = 3;
+const MAX_IDLE_THRESHOLD: i64 = 1440;
+const DEFAULT_GROWTH_DAYS: i64 = 7;
+const MAX_GROWTH_DAYS: i64 = 90;
+const STALE_BACKUP_THRESHOLD: i64 = 3 * 3600;
+const SEASON_CACHE_PERIOD: i64 = 6 * 3600;
+const PERIODICAL_CHECK_PERIOD: u64 = 1800;
+const ANNOUNCE_WINDOW: i64 = 86400;
+const MAX_MESSAGE_LENGTH: usize = 4096;
+const NOTIFICATION_KEEP_PERIOD: i64 = 30 * 86400;
 const DEFAULT_DATABASE_FILE: &str = "spaceship.db";
 
 //const STATIC_DATA: &[u8] = include_bytes!("../out1.data");
@@ -34,6 +47,8 @@ async fn async_main(config_file: &str, database: &str) -> anyhow::Result<()> {
     let (monitor, monitor_helper) = Monitor::create(database_helper.clone(), bot.clone());
     let (subscribe_monitor, subscribe_helper) =
         ContractSubscriber::create(database_helper.clone(), bot.clone());
+    let (periodical_monitor, periodical_helper) =
+        PeriodicalMonitor::create(database_helper.clone(), bot.clone());
 
     bot_run(
         bot,
@@ -46,10 +61,12 @@ async fn async_main(config_file: &str, database: &str) -> anyhow::Result<()> {
 
     monitor_helper.exit().await;
     subscribe_helper.exit().await;
+    periodical_helper.exit().await;
     database_helper.terminate().await;
 
     monitor.join().await?;
     subscribe_monitor.join().await?;
+    periodical_monitor.join().await?;
     database_thread.wait().await?;
 
     Ok(())
@@ -1,17 +1,18 @@
 use chrono::DateTime;
+use chrono_tz::Tz;
 
-pub fn timestamp_to_string(timestamp: i64) -> String {
-    timestamp_fmt(timestamp, "%Y-%m-%d %H:%M:%S")
+pub const DEFAULT_TIMEZONE: Tz = chrono_tz::Asia::Taipei;
+
+pub fn timestamp_to_string(timestamp: i64, tz: Tz) -> String {
+    timestamp_fmt(timestamp, "%Y-%m-%d %H:%M:%S", tz)
 }
 
-pub fn timestamp_fmt(timestamp: i64, fmt: &str) -> String {
+pub fn timestamp_fmt(timestamp: i64, fmt: &str, tz: Tz) -> String {
     let Some(time) = DateTime::from_timestamp(timestamp, 0) else {
         log::warn!("Invalid timestamp: {timestamp}");
         return "N/A".into();
     };
-    time.with_timezone(&chrono_tz::Asia::Taipei)
-        .format(fmt)
-        .to_string()
+    time.with_timezone(&tz).format(fmt).to_string()
 }
 
 pub const BASE64: base64::engine::GeneralPurpose = base64::engine::general_purpose::STANDARD_NO_PAD;